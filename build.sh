@@ -13,3 +13,8 @@ OBJECT=$1
 
 echo "Building object: $OBJECT"
 cargo build -p press-$OBJECT --lib --target wasm32-unknown-unknown --release
+
+# The hut's construction sheet is generated from the model
+if [ "$OBJECT" = "hut" ]; then
+    cargo run -p press-hut --release -- drawing
+fi
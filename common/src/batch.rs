@@ -0,0 +1,50 @@
+//! Sampling many points at once.
+//!
+//! Native tools sample millions of points one `sample` call at a time. [`sample_batch`]
+//! spreads a slice of points over the rayon thread pool on native targets and runs serially
+//! on wasm32, which has no threads in the viewer. Each point is still sampled on its own, so
+//! the results are identical to the per-point path whatever the thread count.
+
+use cgmath::Vector3;
+use sdf_viewer::sdf::{SDFSample, SDFSurface};
+
+#[cfg(not(target_arch = "wasm32"))]
+use rayon::prelude::*;
+
+/// Points per rayon task, enough to outweigh the scheduling of cheap samples.
+#[cfg(not(target_arch = "wasm32"))]
+const MIN_CHUNK: usize = 256;
+
+/// Number of threads a batch is spread over.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn threads() -> usize {
+    rayon::current_num_threads()
+}
+
+/// Number of threads a batch is spread over.
+#[cfg(target_arch = "wasm32")]
+pub fn threads() -> usize {
+    1
+}
+
+/// `f` of each of `points`, in order.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn map_points<T: Send>(points: &[Vector3<f32>], f: impl Fn(Vector3<f32>) -> T + Sync + Send) -> Vec<T> {
+    points.par_iter().with_min_len(MIN_CHUNK).map(|&p| f(p)).collect()
+}
+
+/// `f` of each of `points`, in order.
+#[cfg(target_arch = "wasm32")]
+pub fn map_points<T: Send>(points: &[Vector3<f32>], f: impl Fn(Vector3<f32>) -> T + Sync + Send) -> Vec<T> {
+    points.iter().map(|&p| f(p)).collect()
+}
+
+/// `surface.sample(p, distance_only)` for each of `points`, in order.
+pub fn sample_batch<S: SDFSurface + Sync + ?Sized>(surface: &S, points: &[Vector3<f32>], distance_only: bool) -> Vec<SDFSample> {
+    map_points(points, |p| surface.sample(p, distance_only))
+}
+
+/// The distances at `points`, sampled with `distance_only`.
+pub fn distance_batch<S: SDFSurface + Sync + ?Sized>(surface: &S, points: &[Vector3<f32>]) -> Vec<f32> {
+    map_points(points, |p| surface.sample(p, true).distance)
+}
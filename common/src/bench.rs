@@ -0,0 +1,112 @@
+//! Timing for the `cargo bench` harnesses of the objects.
+//!
+//! No statistics framework: a measurement is the fastest of a few rounds over the same work.
+//! Compared paths take turns round by round, so drift of the machine hits them alike. That is
+//! stable enough to compare paths of one object on one machine.
+
+use std::fmt;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use cgmath::Vector3;
+use sdf_viewer::sdf::SDFSurface;
+
+use crate::batch::{sample_batch, threads};
+
+/// Rounds per measurement, the fastest counts.
+pub const ROUNDS: usize = 7;
+
+#[derive(Debug, Clone)]
+pub struct Timing {
+    pub name: String,
+    /// Samples per round.
+    pub samples: usize,
+    /// Fastest round.
+    pub best: Duration,
+}
+
+impl Timing {
+    pub fn per_sample(&self) -> Duration {
+        self.best / self.samples.max(1) as u32
+    }
+
+    /// How many times faster than `baseline` per sample.
+    pub fn speedup(&self, baseline: &Timing) -> f64 {
+        baseline.per_sample().as_secs_f64() / self.per_sample().as_secs_f64()
+    }
+}
+
+impl fmt::Display for Timing {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:<32} {:>10.1} ns/sample  {:>9.3} ms for {} samples",
+            self.name,
+            self.per_sample().as_secs_f64() * 1e9,
+            self.best.as_secs_f64() * 1e3,
+            self.samples
+        )
+    }
+}
+
+/// Runs each named round [`ROUNDS`] times, taking turns, each doing `samples` samples.
+pub fn time_rounds(samples: usize, rounds: &mut [(&str, &mut dyn FnMut())]) -> Vec<Timing> {
+    let mut best = vec![Duration::MAX; rounds.len()];
+    for _ in 0..ROUNDS {
+        for ((_, round), best) in rounds.iter_mut().zip(&mut best) {
+            let start = Instant::now();
+            round();
+            *best = (*best).min(start.elapsed());
+        }
+    }
+    rounds.iter().zip(best).map(|((name, _), best)| Timing { name: name.to_string(), samples, best }).collect()
+}
+
+/// Samples `surface` at all `points`.
+fn sample_all<S: SDFSurface + ?Sized>(surface: &S, points: &[Vector3<f32>], distance_only: bool) {
+    for &p in points {
+        black_box(surface.sample(black_box(p), distance_only));
+    }
+}
+
+/// Times `surface` with and without `distance_only` and prints both and the speed-up.
+pub fn compare_distance_only<S: SDFSurface + ?Sized>(name: &str, surface: &S, points: &[Vector3<f32>]) -> Vec<Timing> {
+    let (full, distance) = (format!("{} full", name), format!("{} distance only", name));
+    let timings = time_rounds(
+        points.len(),
+        &mut [
+            (&full, &mut || sample_all(surface, points, false)),
+            (&distance, &mut || sample_all(surface, points, true)),
+        ],
+    );
+    println!("{}\n{}  x{:.2}", timings[0], timings[1], timings[1].speedup(&timings[0]));
+    timings
+}
+
+/// Times `surface` point by point and with [`sample_batch`] on all threads, prints both and the
+/// speed-up, the batch named with the thread count. Panics if the batch differs from the
+/// per-point samples.
+pub fn compare_batch<S: SDFSurface + Sync + ?Sized>(name: &str, surface: &S, points: &[Vector3<f32>]) -> Vec<Timing> {
+    let batch = sample_batch(surface, points, false);
+    assert!(
+        points.iter().zip(&batch).all(|(&p, b)| {
+            let s = surface.sample(p, false);
+            s.distance.to_bits() == b.distance.to_bits() && s.color == b.color
+        }),
+        "{}: batch differs from the per-point samples",
+        name
+    );
+
+    let (serial, batch) = (format!("{} per point", name), format!("{} batch on {}", name, threads()));
+    let timings = time_rounds(
+        points.len(),
+        &mut [
+            (&serial, &mut || sample_all(surface, points, false)),
+            (&batch, &mut || {
+                black_box(sample_batch(surface, black_box(points), false));
+            }),
+        ],
+    );
+    println!("{}\n{}  x{:.2}", timings[0], timings[1], timings[1].speedup(&timings[0]));
+    timings
+}
@@ -0,0 +1,271 @@
+//! Bounding box derivation and tightness checks.
+//!
+//! The extent of the negative region is found by an adaptive octree search: a cell is
+//! skipped when its center distance proves it cannot contain negative samples (assuming a
+//! Lipschitz bound), or when it lies entirely inside the extent found so far.
+
+use std::fmt;
+
+use cgmath::{InnerSpace, Vector3};
+use sdf_viewer::sdf::{SDFSample, SDFSurface};
+
+/// Parameters of the extent search.
+#[derive(Debug, Clone)]
+pub struct BoundsConfig {
+    /// The search box is the declared bounding box grown by this fraction of its size per side.
+    pub search_margin: f32,
+    /// Stop subdividing at cells smaller than this fraction of the search box size.
+    pub resolution: f32,
+    /// Assumed Lipschitz constant of the distance field, see `validate::check_lipschitz`.
+    pub lipschitz: f32,
+    /// Declared boxes larger than the extent by more than this fraction of the extent size
+    /// (per face) are reported as loose.
+    pub loose_tolerance: f32,
+}
+
+impl Default for BoundsConfig {
+    fn default() -> Self {
+        Self {
+            search_margin: 0.5,
+            resolution: 1.0 / 512.0,
+            lipschitz: 1.5,
+            loose_tolerance: 0.1,
+        }
+    }
+}
+
+/// Extent of the negative region within the search box.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Extent {
+    pub min: Vector3<f32>,
+    pub max: Vector3<f32>,
+    /// Size of the finest cells, the extent is accurate up to about this much per face.
+    pub accuracy: f32,
+    /// The negative region reaches the search box, it may extend further.
+    pub touches_search_box: bool,
+}
+
+impl Extent {
+    /// The extent grown by `padding` on every side, usable as a bounding box.
+    pub fn padded(&self, padding: f32) -> [Vector3<f32>; 2] {
+        let pad = Vector3::new(padding, padding, padding);
+        [self.min - pad, self.max + pad]
+    }
+}
+
+/// Find the extent of the negative region of `surface` by adaptive search.
+///
+/// Returns `None` when no negative sample was found.
+pub fn negative_extent<S: SDFSurface + ?Sized>(surface: &S, cfg: &BoundsConfig) -> Option<Extent> {
+    let [lo, hi] = surface.bounding_box();
+    let margin = (hi - lo) * cfg.search_margin;
+    let (search_lo, search_hi) = (lo - margin, hi + margin);
+    let size = search_hi - search_lo;
+    let min_cell = size.x.max(size.y).max(size.z) * cfg.resolution;
+
+    let mut extent: Option<(Vector3<f32>, Vector3<f32>)> = None;
+    let mut cells = vec![(search_lo, search_hi)];
+    let mut cell_size = size;
+    while !cells.is_empty() {
+        let half_diagonal = cell_size.magnitude() / 2.0;
+        let leaf = cell_size.x.max(cell_size.y).max(cell_size.z) <= min_cell;
+        let mut next = vec![];
+        for (cmin, cmax) in cells {
+            if let Some((emin, emax)) = extent {
+                if contains(emin, emax, cmin, cmax) {
+                    continue;
+                }
+            }
+            let center = (cmin + cmax) / 2.0;
+            let d = surface.sample(center, true).distance;
+            if d < 0.0 {
+                extent = Some(match extent {
+                    Some((emin, emax)) => (min(emin, center), max(emax, center)),
+                    None => (center, center),
+                });
+            }
+            if leaf || d > cfg.lipschitz * half_diagonal {
+                continue;
+            }
+            if d < -cfg.lipschitz * half_diagonal {
+                // Whole cell is inside
+                let (emin, emax) = extent.unwrap();
+                extent = Some((min(emin, cmin), max(emax, cmax)));
+                continue;
+            }
+            for octant in 0..8 {
+                let pick = |bit: usize, lo: f32, mid: f32, hi: f32| {
+                    if octant & bit == 0 { (lo, mid) } else { (mid, hi) }
+                };
+                let (x0, x1) = pick(1, cmin.x, center.x, cmax.x);
+                let (y0, y1) = pick(2, cmin.y, center.y, cmax.y);
+                let (z0, z1) = pick(4, cmin.z, center.z, cmax.z);
+                next.push((Vector3::new(x0, y0, z0), Vector3::new(x1, y1, z1)));
+            }
+        }
+        cells = next;
+        cell_size /= 2.0;
+    }
+
+    extent.map(|(emin, emax)| {
+        let accuracy = min_cell;
+        let touches_search_box = [
+            emin.x - search_lo.x, emin.y - search_lo.y, emin.z - search_lo.z,
+            search_hi.x - emax.x, search_hi.y - emax.y, search_hi.z - emax.z,
+        ]
+        .iter()
+        .any(|&gap| gap < accuracy);
+        Extent { min: emin, max: emax, accuracy, touches_search_box }
+    })
+}
+
+fn contains(
+    outer_min: Vector3<f32>,
+    outer_max: Vector3<f32>,
+    inner_min: Vector3<f32>,
+    inner_max: Vector3<f32>,
+) -> bool {
+    outer_min.x <= inner_min.x && outer_min.y <= inner_min.y && outer_min.z <= inner_min.z
+        && inner_max.x <= outer_max.x && inner_max.y <= outer_max.y && inner_max.z <= outer_max.z
+}
+
+/// Componentwise minimum.
+pub fn min(a: Vector3<f32>, b: Vector3<f32>) -> Vector3<f32> {
+    Vector3::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z))
+}
+
+/// Componentwise maximum.
+pub fn max(a: Vector3<f32>, b: Vector3<f32>) -> Vector3<f32> {
+    Vector3::new(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z))
+}
+
+const FACES: [&str; 6] = ["-x", "-y", "-z", "+x", "+y", "+z"];
+
+/// Declared bounding box compared with the actual extent of the surface.
+#[derive(Debug, Clone)]
+pub struct BoundsReport {
+    pub declared: [Vector3<f32>; 2],
+    pub extent: Option<Extent>,
+    /// Per face (-x, -y, -z, +x, +y, +z): how far the declared box lies outside the extent.
+    /// Negative values mean the declared box clips the surface.
+    pub slack: [f32; 6],
+    pub loose_tolerance: f32,
+}
+
+impl BoundsReport {
+    /// Faces where the declared box cuts into the surface.
+    pub fn clipped_faces(&self) -> Vec<&'static str> {
+        let accuracy = self.extent.map_or(0.0, |e| e.accuracy);
+        FACES.iter().zip(self.slack).filter(|(_, s)| *s < -accuracy).map(|(f, _)| *f).collect()
+    }
+
+    /// Faces with more slack than `loose_tolerance` times the extent size along that axis.
+    pub fn loose_faces(&self) -> Vec<&'static str> {
+        let Some(e) = self.extent else { return vec![] };
+        let size = e.max - e.min;
+        let sizes = [size.x, size.y, size.z, size.x, size.y, size.z];
+        FACES
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| self.slack[i] > self.loose_tolerance * sizes[i] + e.accuracy)
+            .map(|(_, f)| *f)
+            .collect()
+    }
+
+    /// Declared box volume divided by the extent volume.
+    pub fn looseness(&self) -> f32 {
+        let volume = |a: Vector3<f32>, b: Vector3<f32>| {
+            let s = b - a;
+            s.x * s.y * s.z
+        };
+        match self.extent {
+            Some(e) => volume(self.declared[0], self.declared[1]) / volume(e.min, e.max),
+            None => f32::INFINITY,
+        }
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.extent.is_some_and(|e| !e.touches_search_box)
+            && self.clipped_faces().is_empty()
+            && self.loose_faces().is_empty()
+    }
+}
+
+impl fmt::Display for BoundsReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let [lo, hi] = self.declared;
+        writeln!(f, "declared: ({}, {}, {})..({}, {}, {})", lo.x, lo.y, lo.z, hi.x, hi.y, hi.z)?;
+        let Some(e) = self.extent else {
+            return writeln!(f, "warning: no negative samples found");
+        };
+        writeln!(
+            f,
+            "actual:   ({}, {}, {})..({}, {}, {}) +- {}",
+            e.min.x, e.min.y, e.min.z, e.max.x, e.max.y, e.max.z, e.accuracy
+        )?;
+        for (face, slack) in FACES.iter().zip(self.slack) {
+            writeln!(f, "  {} slack {}", face, slack)?;
+        }
+        writeln!(f, "declared/actual volume: {}", self.looseness())?;
+        if e.touches_search_box {
+            writeln!(f, "warning: surface reaches the search box, it may extend even further")?;
+        }
+        let clipped = self.clipped_faces();
+        if !clipped.is_empty() {
+            writeln!(f, "warning: declared box clips the surface at {}", clipped.join(", "))?;
+        }
+        let loose = self.loose_faces();
+        if !loose.is_empty() {
+            writeln!(f, "warning: declared box is loose at {}", loose.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Compare the declared bounding box of `surface` with its actual extent.
+pub fn check_bounds<S: SDFSurface + ?Sized>(surface: &S, cfg: &BoundsConfig) -> BoundsReport {
+    let declared = surface.bounding_box();
+    let extent = negative_extent(surface, cfg);
+    let slack = match extent {
+        Some(e) => [
+            e.min.x - declared[0].x,
+            e.min.y - declared[0].y,
+            e.min.z - declared[0].z,
+            declared[1].x - e.max.x,
+            declared[1].y - e.max.y,
+            declared[1].z - e.max.z,
+        ],
+        None => [f32::INFINITY; 6],
+    };
+    BoundsReport { declared, extent, slack, loose_tolerance: cfg.loose_tolerance }
+}
+
+/// Wraps a surface and replaces its bounding box with the padded actual extent.
+///
+/// The extent is computed once on construction, using the inner bounding box as the
+/// starting point of the search.
+#[derive(Debug, Clone)]
+pub struct AutoBounds<S> {
+    pub inner: S,
+    pub bounding_box: [Vector3<f32>; 2],
+}
+
+impl<S: SDFSurface> AutoBounds<S> {
+    pub fn new(inner: S, cfg: &BoundsConfig, padding: f32) -> Self {
+        let bounding_box = match negative_extent(&inner, cfg) {
+            Some(e) => e.padded(padding + e.accuracy),
+            None => inner.bounding_box(),
+        };
+        Self { inner, bounding_box }
+    }
+}
+
+impl<S: SDFSurface> SDFSurface for AutoBounds<S> {
+    fn bounding_box(&self) -> [Vector3<f32>; 2] {
+        self.bounding_box
+    }
+
+    fn sample(&self, p: Vector3<f32>, distance_only: bool) -> SDFSample {
+        self.inner.sample(p, distance_only)
+    }
+}
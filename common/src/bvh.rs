@@ -0,0 +1,182 @@
+//! Bounding volume hierarchy over many bounded children.
+//!
+//! Objects built from many copies of a part, like the bricks of the hut, take the minimum
+//! of every copy for each sample. A [`Bvh`] keeps the children in a tree of boxes and visits
+//! them nearest first, skipping every box whose lower bound is no smaller than the best
+//! distance found so far. Only children that cannot be the minimum are skipped, so the result
+//! is the same as the plain minimum, bit for bit.
+//!
+//! The lower bound is `ratio` times the Euclidean distance to a box, and minus infinity inside
+//! it. Children must be non-negative outside their bounds and at least that large: `ratio` is 1
+//! for exact distances such as `cylinder_between`, `1/√3` for boxes written as the largest face
+//! distance. Pad the bounds a little to cover rounding in the children's transforms.
+
+use cgmath::{InnerSpace, Vector3};
+
+/// Largest number of children in a leaf.
+const LEAF_SIZE: usize = 4;
+
+/// Deepest tree the traversal stack holds, far beyond the depth of any median split.
+const MAX_DEPTH: usize = 64;
+
+#[derive(Debug, Clone)]
+enum Node {
+    Leaf { bounds: [Vector3<f32>; 2], start: usize, end: usize },
+    Split { bounds: [Vector3<f32>; 2], children: [usize; 2] },
+}
+
+impl Node {
+    fn bounds(&self) -> [Vector3<f32>; 2] {
+        match *self {
+            Node::Leaf { bounds, .. } | Node::Split { bounds, .. } => bounds,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Bvh<T> {
+    /// Children with their bounds, in leaf order.
+    items: Vec<(T, [Vector3<f32>; 2])>,
+    /// The root first.
+    nodes: Vec<Node>,
+    ratio: f32,
+}
+
+impl<T> Default for Bvh<T> {
+    fn default() -> Self {
+        Self { items: vec![], nodes: vec![], ratio: 1.0 }
+    }
+}
+
+fn union([a, b]: [Vector3<f32>; 2], [c, d]: [Vector3<f32>; 2]) -> [Vector3<f32>; 2] {
+    [
+        Vector3::new(a.x.min(c.x), a.y.min(c.y), a.z.min(c.z)),
+        Vector3::new(b.x.max(d.x), b.y.max(d.y), b.z.max(d.z)),
+    ]
+}
+
+fn center([lo, hi]: &[Vector3<f32>; 2], axis: usize) -> f32 {
+    (lo[axis] + hi[axis]) / 2.0
+}
+
+impl<T> Bvh<T> {
+    /// Tree over `items`, each a child with its world bounds, see the module documentation for
+    /// `ratio`.
+    pub fn new(items: impl IntoIterator<Item = (T, [Vector3<f32>; 2])>, ratio: f32) -> Self {
+        let mut bvh = Self { items: items.into_iter().collect(), nodes: vec![], ratio };
+        if !bvh.items.is_empty() {
+            bvh.build(0, bvh.items.len());
+        }
+        bvh
+    }
+
+    /// Adds the node over `items[start..end]` and returns its index.
+    fn build(&mut self, start: usize, end: usize) -> usize {
+        let items = &mut self.items[start..end];
+        let bounds = items.iter().map(|(_, b)| *b).reduce(union).unwrap();
+        let index = self.nodes.len();
+        if items.len() <= LEAF_SIZE {
+            self.nodes.push(Node::Leaf { bounds, start, end });
+            return index;
+        }
+
+        // Median split of the centers along the axis they spread most
+        let spread = items
+            .iter()
+            .map(|(_, b)| {
+                let c = Vector3::new(center(b, 0), center(b, 1), center(b, 2));
+                [c, c]
+            })
+            .reduce(union)
+            .unwrap();
+        let size = spread[1] - spread[0];
+        let axis = if size.x >= size.y && size.x >= size.z { 0 } else if size.y >= size.z { 1 } else { 2 };
+        let mid = items.len() / 2;
+        items.select_nth_unstable_by(mid, |(_, a), (_, b)| center(a, axis).total_cmp(&center(b, axis)));
+
+        self.nodes.push(Node::Split { bounds, children: [0, 0] });
+        let left = self.build(start, start + mid);
+        let right = self.build(start + mid, end);
+        self.nodes[index] = Node::Split { bounds, children: [left, right] };
+        index
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Bounds of all children, `None` if there are none.
+    pub fn bounds(&self) -> Option<[Vector3<f32>; 2]> {
+        self.nodes.first().map(Node::bounds)
+    }
+
+    /// The children with their bounds, in tree order.
+    pub fn iter(&self) -> impl Iterator<Item = &(T, [Vector3<f32>; 2])> {
+        self.items.iter()
+    }
+
+    /// Smallest value a child can take at `p` given its bounds.
+    fn lower_bound(&self, p: Vector3<f32>, [lo, hi]: [Vector3<f32>; 2]) -> f32 {
+        let outside = Vector3::new(
+            (lo.x - p.x).max(p.x - hi.x).max(0.0),
+            (lo.y - p.y).max(p.y - hi.y).max(0.0),
+            (lo.z - p.z).max(p.z - hi.z).max(0.0),
+        );
+        if outside.x == 0.0 && outside.y == 0.0 && outside.z == 0.0 {
+            f32::NEG_INFINITY
+        } else {
+            self.ratio * outside.magnitude()
+        }
+    }
+
+    /// The child with the smallest `distance` at `p` and that distance, evaluating only children
+    /// whose bounds are nearer than the best distance found before them.
+    pub fn nearest(&self, p: Vector3<f32>, mut distance: impl FnMut(&T) -> f32) -> Option<(&T, f32)> {
+        let mut best: Option<(&T, f32)> = None;
+        let best_distance = |best: &Option<(&T, f32)>| best.map_or(f32::INFINITY, |(_, d)| d);
+        let mut stack = [(0, 0.0); MAX_DEPTH];
+        let mut len = 0;
+        if let Some(root) = self.nodes.first() {
+            stack[0] = (0, self.lower_bound(p, root.bounds()));
+            len = 1;
+        }
+        while len > 0 {
+            len -= 1;
+            let (index, bound) = stack[len];
+            if bound >= best_distance(&best) {
+                continue;
+            }
+            match self.nodes[index] {
+                Node::Leaf { start, end, .. } => {
+                    for (item, bounds) in &self.items[start..end] {
+                        if self.lower_bound(p, *bounds) < best_distance(&best) {
+                            let d = distance(item);
+                            if d < best_distance(&best) {
+                                best = Some((item, d));
+                            }
+                        }
+                    }
+                }
+                Node::Split { children, .. } => {
+                    let [a, b] = children.map(|c| (c, self.lower_bound(p, self.nodes[c].bounds())));
+                    // The nearer child on top, so it is visited first
+                    let (near, far) = if a.1 <= b.1 { (a, b) } else { (b, a) };
+                    stack[len] = far;
+                    stack[len + 1] = near;
+                    len += 2;
+                }
+            }
+        }
+        best
+    }
+
+    /// Smallest `distance` of any child at `p`, infinite without children. The same as the
+    /// minimum over all children.
+    pub fn distance(&self, p: Vector3<f32>, distance: impl FnMut(&T) -> f32) -> f32 {
+        self.nearest(p, distance).map_or(f32::INFINITY, |(_, d)| d)
+    }
+}
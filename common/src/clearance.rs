@@ -0,0 +1,187 @@
+//! Clearance and interference between the parts of an object.
+//!
+//! For two distance fields `a` and `b` the smallest value of `max(a(p), b(p))` is half their
+//! separation: positive halfway between the closest points (half the gap), negative inside
+//! both (half the penetration depth). It is found from the best points of a grid over the
+//! bounding box, each refined by a pattern search. Fields that underestimate the distance
+//! make the reported gaps conservative.
+
+use std::fmt;
+
+use cgmath::Vector3;
+
+use crate::part::{PartId, Parts};
+
+/// Parameters of [`clearances`].
+#[derive(Debug, Clone)]
+pub struct ClearanceConfig {
+    /// Smallest allowed gap between two parts, pairs below it fail the check.
+    pub min_clearance: f32,
+    /// Grid points per axis of the bounding box the search starts from.
+    pub seeds: usize,
+    /// Best grid points of each pair refined by the pattern search, parts that are not
+    /// convex can have several local minima.
+    pub starts: usize,
+    /// Accuracy of the closest approach point.
+    pub accuracy: f32,
+    /// Pairs that touch by design, e.g. a part mounted in a pocket of another, not checked.
+    pub ignore: Vec<(PartId, PartId)>,
+}
+
+impl Default for ClearanceConfig {
+    fn default() -> Self {
+        Self {
+            min_clearance: 0.0,
+            seeds: 48,
+            starts: 8,
+            accuracy: 1e-3,
+            ignore: vec![],
+        }
+    }
+}
+
+/// Closest approach of two parts, or of two bodies of another kind named by `Id`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Clearance<Id = PartId> {
+    pub a: Id,
+    pub b: Id,
+    /// Gap between the parts, negative values are penetration depths.
+    pub separation: f32,
+    /// Middle of the gap, or the deepest common point of overlapping parts.
+    pub point: Vector3<f32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ClearanceReport {
+    /// Part names indexed by part ID.
+    pub names: Vec<String>,
+    /// Every checked pair of non-empty parts, closest first.
+    pub pairs: Vec<Clearance>,
+    pub min_clearance: f32,
+}
+
+impl ClearanceReport {
+    /// Pairs closer than `min_clearance`.
+    pub fn violations(&self) -> Vec<&Clearance> {
+        self.pairs.iter().filter(|c| c.separation < self.min_clearance).collect()
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.violations().is_empty()
+    }
+}
+
+impl fmt::Display for ClearanceReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{} pairs, minimum clearance {}", self.pairs.len(), self.min_clearance)?;
+        for c in &self.pairs {
+            writeln!(
+                f,
+                "  {:>6} {} / {}: {} {:.3} at ({:.3}, {:.3}, {:.3})",
+                if c.separation < self.min_clearance { "FAIL" } else { "ok" },
+                self.names[c.a],
+                self.names[c.b],
+                if c.separation < 0.0 { "overlap" } else { "gap" },
+                c.separation.abs(),
+                c.point.x,
+                c.point.y,
+                c.point.z
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Closest approach of every pair of parts of `surface`.
+///
+/// Parts with an infinite distance everywhere on the grid are empty and skipped.
+pub fn clearances<S: Parts + ?Sized>(surface: &S, cfg: &ClearanceConfig) -> ClearanceReport {
+    let names: Vec<String> = surface.parts().into_iter().map(|part| part.name).collect();
+    let [lo, hi] = surface.bounding_box();
+    let cell = (hi - lo) / cfg.seeds as f32;
+    let mut points = Vec::with_capacity(cfg.seeds.pow(3));
+    let mut distances = Vec::with_capacity(cfg.seeds.pow(3));
+    for k in 0..cfg.seeds {
+        for j in 0..cfg.seeds {
+            for i in 0..cfg.seeds {
+                let p = lo + Vector3::new((i as f32 + 0.5) * cell.x, (j as f32 + 0.5) * cell.y, (k as f32 + 0.5) * cell.z);
+                points.push(p);
+                distances.push(surface.part_distances(p));
+            }
+        }
+    }
+    let empty: Vec<bool> = (0..names.len()).map(|i| distances.iter().all(|d| d[i].is_infinite())).collect();
+
+    let mut pairs = vec![];
+    for a in 0..names.len() {
+        for b in a + 1..names.len() {
+            if empty[a] || empty[b] || cfg.ignore.contains(&(a, b)) || cfg.ignore.contains(&(b, a)) {
+                continue;
+            }
+            let mut seeds: Vec<(Vector3<f32>, f32)> =
+                points.iter().zip(&distances).map(|(&p, d)| (p, d[a].max(d[b]))).collect();
+            seeds.sort_by(|x, y| x.1.total_cmp(&y.1));
+            let f = |p| surface.part_distance(a, p).max(surface.part_distance(b, p));
+            let (point, value) = seeds
+                .into_iter()
+                .take(cfg.starts)
+                .map(|seed| pattern_search(f, seed, cell / 2.0, cfg.accuracy / 4.0))
+                .min_by(|x, y| x.1.total_cmp(&y.1))
+                .unwrap();
+            pairs.push(Clearance { a, b, separation: 2.0 * value, point });
+        }
+    }
+    pairs.sort_by(|x, y| x.separation.total_cmp(&y.separation));
+    ClearanceReport { names, pairs, min_clearance: cfg.min_clearance }
+}
+
+/// Minimize a convex function over the box `[lo, hi]`: best of a `seeds`^3 grid, refined by
+/// [`pattern_search`] until the step drops below `accuracy`.
+pub fn minimize(f: impl Fn(Vector3<f32>) -> f32, lo: Vector3<f32>, hi: Vector3<f32>, seeds: usize, accuracy: f32) -> (Vector3<f32>, f32) {
+    let cell = (hi - lo) / seeds as f32;
+    let mut best = (lo, f32::INFINITY);
+    for k in 0..seeds {
+        for j in 0..seeds {
+            for i in 0..seeds {
+                let p = lo + Vector3::new((i as f32 + 0.5) * cell.x, (j as f32 + 0.5) * cell.y, (k as f32 + 0.5) * cell.z);
+                let v = f(p);
+                if v < best.1 {
+                    best = (p, v);
+                }
+            }
+        }
+    }
+    pattern_search(f, best, cell / 2.0, accuracy)
+}
+
+/// Local minimum of `f` from `start` (a point and its value): moves to any better of the 26
+/// neighbours at `step` and halves the step when none is better, until it drops below
+/// `accuracy`.
+pub fn pattern_search(
+    f: impl Fn(Vector3<f32>) -> f32,
+    start: (Vector3<f32>, f32),
+    step: Vector3<f32>,
+    accuracy: f32,
+) -> (Vector3<f32>, f32) {
+    let mut best = start;
+    let mut step = step;
+    while step.x.max(step.y).max(step.z) > accuracy {
+        let mut improved = false;
+        for d in 0..27 {
+            if d == 13 {
+                continue;
+            }
+            let offset = Vector3::new((d % 3) as f32 - 1.0, (d / 3 % 3) as f32 - 1.0, (d / 9) as f32 - 1.0);
+            let p = best.0 + Vector3::new(offset.x * step.x, offset.y * step.y, offset.z * step.z);
+            let v = f(p);
+            if v < best.1 {
+                best = (p, v);
+                improved = true;
+            }
+        }
+        if !improved {
+            step /= 2.0;
+        }
+    }
+    best
+}
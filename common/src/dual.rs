@@ -0,0 +1,202 @@
+//! Forward-mode automatic differentiation.
+//!
+//! A [`Dual`] carries the partial derivatives by x, y and z along with its value. Distance code
+//! written against [`Scalar`] and evaluated on [`Dual::variables`] returns the distance and its
+//! exact gradient in one pass, where finite differences take six more samples and lose accuracy
+//! at creases. At the kinks of `abs`, `min` and `max` the gradient of the selected side is
+//! taken, at the origin of `sqrt` it is zero.
+
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use cgmath::{InnerSpace, Vector3, Zero};
+
+use crate::scalar::Scalar;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Dual {
+    pub value: f64,
+    /// Partial derivatives by x, y and z.
+    pub gradient: Vector3<f64>,
+}
+
+impl Dual {
+    pub fn new(value: f64, gradient: Vector3<f64>) -> Self {
+        Self { value, gradient }
+    }
+
+    pub fn constant(value: f64) -> Self {
+        Self::new(value, Vector3::zero())
+    }
+
+    /// The coordinates of `p` as the independent variables.
+    pub fn variables(p: Vector3<f64>) -> Vector3<Dual> {
+        Vector3::new(
+            Self::new(p.x, Vector3::unit_x()),
+            Self::new(p.y, Vector3::unit_y()),
+            Self::new(p.z, Vector3::unit_z()),
+        )
+    }
+
+    /// Applies a function with value `value` and derivative `derivative` at `self.value`.
+    fn chain(self, value: f64, derivative: f64) -> Self {
+        Self::new(value, self.gradient * derivative)
+    }
+
+    pub fn abs(self) -> Self {
+        if self.value < 0.0 { -self } else { self }
+    }
+
+    pub fn sqrt(self) -> Self {
+        let s = self.value.sqrt();
+        if s > 0.0 { self.chain(s, 0.5 / s) } else { Self::constant(s) }
+    }
+
+    pub fn sin(self) -> Self {
+        self.chain(self.value.sin(), self.value.cos())
+    }
+
+    pub fn cos(self) -> Self {
+        self.chain(self.value.cos(), -self.value.sin())
+    }
+
+    pub fn exp(self) -> Self {
+        let e = self.value.exp();
+        self.chain(e, e)
+    }
+
+    pub fn powi(self, n: i32) -> Self {
+        self.chain(self.value.powi(n), n as f64 * self.value.powi(n - 1))
+    }
+
+    pub fn atan2(self, other: Self) -> Self {
+        let r2 = self.value * self.value + other.value * other.value;
+        let gradient = if r2 > 0.0 {
+            (self.gradient * other.value - other.gradient * self.value) / r2
+        } else {
+            Vector3::zero()
+        };
+        Self::new(self.value.atan2(other.value), gradient)
+    }
+
+    pub fn min(self, other: Self) -> Self {
+        if other.value < self.value { other } else { self }
+    }
+
+    pub fn max(self, other: Self) -> Self {
+        if other.value > self.value { other } else { self }
+    }
+
+    pub fn clamp(self, min: f64, max: f64) -> Self {
+        self.max(Self::constant(min)).min(Self::constant(max))
+    }
+
+    /// The gradient as a unit normal, zero where it vanishes.
+    pub fn normal(self) -> Vector3<f64> {
+        let m = self.gradient.magnitude();
+        if m > 0.0 { self.gradient / m } else { Vector3::zero() }
+    }
+}
+
+impl Scalar for Dual {
+    fn from_f64(value: f64) -> Self {
+        Self::constant(value)
+    }
+    fn value(self) -> f64 {
+        self.value
+    }
+    fn abs(self) -> Self {
+        Dual::abs(self)
+    }
+    fn sqrt(self) -> Self {
+        Dual::sqrt(self)
+    }
+    fn sin(self) -> Self {
+        Dual::sin(self)
+    }
+    fn cos(self) -> Self {
+        Dual::cos(self)
+    }
+    fn atan2(self, other: Self) -> Self {
+        Dual::atan2(self, other)
+    }
+    fn min(self, other: Self) -> Self {
+        Dual::min(self, other)
+    }
+    fn max(self, other: Self) -> Self {
+        Dual::max(self, other)
+    }
+}
+
+impl From<f64> for Dual {
+    fn from(value: f64) -> Self {
+        Self::constant(value)
+    }
+}
+
+impl Neg for Dual {
+    type Output = Dual;
+    fn neg(self) -> Dual {
+        Dual::new(-self.value, -self.gradient)
+    }
+}
+
+impl Add for Dual {
+    type Output = Dual;
+    fn add(self, other: Dual) -> Dual {
+        Dual::new(self.value + other.value, self.gradient + other.gradient)
+    }
+}
+
+impl Sub for Dual {
+    type Output = Dual;
+    fn sub(self, other: Dual) -> Dual {
+        Dual::new(self.value - other.value, self.gradient - other.gradient)
+    }
+}
+
+impl Mul for Dual {
+    type Output = Dual;
+    fn mul(self, other: Dual) -> Dual {
+        Dual::new(self.value * other.value, self.gradient * other.value + other.gradient * self.value)
+    }
+}
+
+impl Div for Dual {
+    type Output = Dual;
+    fn div(self, other: Dual) -> Dual {
+        Dual::new(
+            self.value / other.value,
+            (self.gradient * other.value - other.gradient * self.value) / (other.value * other.value),
+        )
+    }
+}
+
+/// Mixed arithmetic with plain numbers, so `f64` code ports by changing the types.
+macro_rules! impl_dual_f64_op {
+    ($op:ident, $method:ident) => {
+        impl $op<f64> for Dual {
+            type Output = Dual;
+            fn $method(self, other: f64) -> Dual {
+                $op::$method(self, Dual::constant(other))
+            }
+        }
+
+        impl $op<Dual> for f64 {
+            type Output = Dual;
+            fn $method(self, other: Dual) -> Dual {
+                $op::$method(Dual::constant(self), other)
+            }
+        }
+    };
+}
+
+impl_dual_f64_op!(Add, add);
+impl_dual_f64_op!(Sub, sub);
+impl_dual_f64_op!(Mul, mul);
+impl_dual_f64_op!(Div, div);
+
+/// Distance and gradient of `distance` at `p`, evaluated once on [`Dual`] numbers.
+pub fn distance_gradient(distance: impl Fn(Vector3<Dual>) -> Dual, p: Vector3<f32>) -> (f32, Vector3<f32>) {
+    let d = distance(Dual::variables(p.cast().unwrap()));
+    (d.value as f32, d.gradient.cast().unwrap())
+}
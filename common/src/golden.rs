@@ -0,0 +1,254 @@
+//! Golden-field regression tests for SDF objects.
+//!
+//! The distance field is sampled on a fixed grid (or a slice plane) and compared with a
+//! snapshot committed next to the tests. Set `PRESS_UPDATE_GOLDEN=1` to (re)write the
+//! snapshots after an intended geometry change:
+//!
+//! ```text
+//! PRESS_UPDATE_GOLDEN=1 cargo test -p press-mosquito
+//! ```
+
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+use cgmath::Vector3;
+use sdf_viewer::sdf::SDFSurface;
+
+use crate::batch::sample_batch;
+use crate::slice::{Slice, SlicePlane};
+
+/// Environment variable that switches the asserts to writing snapshots.
+pub const UPDATE_ENV: &str = "PRESS_UPDATE_GOLDEN";
+
+/// Number of worst points listed when a comparison fails.
+const REPORT_POINTS: usize = 10;
+
+/// Distances sampled at known positions, described by a header that must match the snapshot.
+#[derive(Debug, Clone)]
+pub struct Field {
+    pub header: String,
+    pub points: Vec<Vector3<f32>>,
+    pub distances: Vec<f32>,
+}
+
+impl Field {
+    /// Sample `surface` at the centers of a `resolution`^3 grid spanning its bounding box.
+    pub fn grid<S: SDFSurface + ?Sized>(surface: &S, resolution: usize) -> Self {
+        let [lo, hi] = surface.bounding_box();
+        let cell = (hi - lo) / resolution as f32;
+        let mut points = Vec::with_capacity(resolution.pow(3));
+        for k in 0..resolution {
+            for j in 0..resolution {
+                for i in 0..resolution {
+                    points.push(Vector3::new(
+                        lo.x + (i as f32 + 0.5) * cell.x,
+                        lo.y + (j as f32 + 0.5) * cell.y,
+                        lo.z + (k as f32 + 0.5) * cell.z,
+                    ));
+                }
+            }
+        }
+        let distances = points.iter().map(|&p| surface.sample(p, true).distance).collect();
+        let header = format!(
+            "grid {} bbox {} {} {} {} {} {}",
+            resolution, lo.x, lo.y, lo.z, hi.x, hi.y, hi.z
+        );
+        Self { header, points, distances }
+    }
+
+    /// Sample `surface` on a slice plane, see [`Slice::sample`].
+    pub fn slice<S: SDFSurface + ?Sized>(surface: &S, plane: SlicePlane, step: f32) -> Self {
+        Self::from(&Slice::sample(surface, plane, step))
+    }
+
+    fn serialize(&self) -> String {
+        let mut out = format!("{}\n", self.header);
+        for d in &self.distances {
+            writeln!(out, "{}", d).unwrap();
+        }
+        out
+    }
+
+    /// Compare against the stored `expected` snapshot, listing the worst differences on failure.
+    fn compare(&self, expected: &str, tolerance: f32) -> Result<(), String> {
+        let mut lines = expected.lines();
+        let header = lines.next().unwrap_or_default();
+        if header != self.header {
+            return Err(format!(
+                "sampling changed (bounding box or resolution)\n  expected: {}\n  actual:   {}",
+                header, self.header
+            ));
+        }
+        let expected = lines
+            .map(|l| l.trim().parse::<f32>().map_err(|e| format!("corrupt snapshot line `{}`: {}", l, e)))
+            .collect::<Result<Vec<_>, _>>()?;
+        if expected.len() != self.distances.len() {
+            return Err(format!(
+                "snapshot has {} samples, expected {}",
+                expected.len(),
+                self.distances.len()
+            ));
+        }
+
+        let mut diffs = expected
+            .iter()
+            .zip(&self.distances)
+            .enumerate()
+            .map(|(i, (&e, &a))| (i, e, a, (a - e).abs()))
+            .filter(|&(_, e, a, diff)| diff > tolerance || e.is_nan() != a.is_nan())
+            .collect::<Vec<_>>();
+        if diffs.is_empty() {
+            return Ok(());
+        }
+
+        diffs.sort_by(|a, b| b.3.total_cmp(&a.3));
+        let mut report = format!(
+            "{} of {} samples differ by more than {}, largest differences:\n",
+            diffs.len(),
+            self.distances.len(),
+            tolerance
+        );
+        for &(i, e, a, diff) in diffs.iter().take(REPORT_POINTS) {
+            let p = self.points[i];
+            writeln!(
+                report,
+                "  ({:.3}, {:.3}, {:.3}): expected {}, got {} (diff {})",
+                p.x, p.y, p.z, e, a, diff
+            )
+            .unwrap();
+        }
+        Err(report)
+    }
+
+    /// Check the field against the snapshot at `path` within `tolerance`.
+    ///
+    /// With `PRESS_UPDATE_GOLDEN` set, the snapshot is written instead.
+    pub fn check_golden(&self, path: impl AsRef<Path>, tolerance: f32) -> Result<(), String> {
+        let path = path.as_ref();
+        if std::env::var_os(UPDATE_ENV).is_some() {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+            }
+            return fs::write(path, self.serialize())
+                .map_err(|e| format!("cannot write snapshot {}: {}", path.display(), e));
+        }
+
+        let expected = fs::read_to_string(path).map_err(|e| {
+            format!(
+                "cannot read snapshot {}: {}\nrun with {}=1 to create it",
+                path.display(),
+                e,
+                UPDATE_ENV
+            )
+        })?;
+        self.compare(&expected, tolerance).map_err(|report| {
+            format!(
+                "golden mismatch for {}: {}\nrun with {}=1 if the change is intended",
+                path.display(),
+                report,
+                UPDATE_ENV
+            )
+        })
+    }
+
+    /// Panicking version of [`Field::check_golden`] for use in tests.
+    pub fn assert_golden(&self, path: impl AsRef<Path>, tolerance: f32) {
+        if let Err(report) = self.check_golden(path, tolerance) {
+            panic!("{}", report);
+        }
+    }
+}
+
+impl From<&Slice> for Field {
+    fn from(slice: &Slice) -> Self {
+        let mut points = Vec::with_capacity(slice.distances.len());
+        for j in 0..slice.height {
+            for i in 0..slice.width {
+                let [s, t] = slice.coords(i, j);
+                points.push(slice.plane.point(s, t));
+            }
+        }
+        let (o, n) = (slice.plane.origin, slice.plane.normal);
+        let header = format!(
+            "slice {} {} {} normal {} {} {} min {} {} step {} size {} {}",
+            o.x, o.y, o.z, n.x, n.y, n.z, slice.min[0], slice.min[1], slice.step, slice.width,
+            slice.height
+        );
+        Self { header, points, distances: slice.distances.clone() }
+    }
+}
+
+/// Sample `surface` on a `resolution`^3 grid and compare it with the snapshot at `path`.
+pub fn assert_golden_field<S: SDFSurface + ?Sized>(
+    surface: &S,
+    path: impl AsRef<Path>,
+    resolution: usize,
+    tolerance: f32,
+) {
+    Field::grid(surface, resolution).assert_golden(path, tolerance);
+}
+
+/// Slice `surface` and compare the distances with the snapshot at `path`.
+///
+/// On mismatch the actual slice is written next to the snapshot as `<path>.actual.png`
+/// and `<path>.actual.svg` for visual inspection.
+pub fn assert_golden_slice<S: SDFSurface + ?Sized>(
+    surface: &S,
+    plane: SlicePlane,
+    step: f32,
+    path: impl AsRef<Path>,
+    tolerance: f32,
+) {
+    let path = path.as_ref();
+    let slice = Slice::sample(surface, plane, step);
+    if let Err(report) = Field::from(&slice).check_golden(path, tolerance) {
+        let _ = slice.write(&format!("{}.actual", path.display()));
+        panic!("{}", report);
+    }
+}
+
+/// Sample `surface` on a `resolution`^3 grid with and without `distance_only` and assert that
+/// both paths give the same distances, so the cheaper path the tools take matches the viewer.
+pub fn assert_distance_only<S: SDFSurface + ?Sized>(surface: &S, resolution: usize) {
+    let field = Field::grid(surface, resolution);
+    let mismatches = field
+        .points
+        .iter()
+        .zip(&field.distances)
+        .filter(|&(&p, &d)| surface.sample(p, false).distance.to_bits() != d.to_bits())
+        .collect::<Vec<_>>();
+    if let Some(&(p, d)) = mismatches.first() {
+        panic!(
+            "{} of {} distances differ with distance_only, first at ({:.3}, {:.3}, {:.3}): {} against {}",
+            mismatches.len(),
+            field.distances.len(),
+            p.x,
+            p.y,
+            p.z,
+            d,
+            surface.sample(*p, false).distance
+        );
+    }
+}
+
+/// Sample `surface` on a `resolution`^3 grid with [`sample_batch`] and assert that it gives
+/// the same distances and colours as sampling point by point.
+pub fn assert_batch<S: SDFSurface + Sync + ?Sized>(surface: &S, resolution: usize) {
+    let points = Field::grid(surface, resolution).points;
+    for distance_only in [true, false] {
+        let batch = sample_batch(surface, &points, distance_only);
+        for (&p, b) in points.iter().zip(&batch) {
+            let s = surface.sample(p, distance_only);
+            assert!(
+                s.distance.to_bits() == b.distance.to_bits() && s.color == b.color,
+                "batch differs at ({:.3}, {:.3}, {:.3}): {} against {}",
+                p.x,
+                p.y,
+                p.z,
+                b.distance,
+                s.distance
+            );
+        }
+    }
+}
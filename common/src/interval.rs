@@ -0,0 +1,309 @@
+//! Interval arithmetic for conservative evaluation over boxes.
+//!
+//! An [`Interval`] holds every value a quantity can take over a region. Distance code written
+//! against [`Scalar`] and evaluated on [`Interval::from_box`] bounds the distance over the whole
+//! box, so a box whose interval lies above zero is proven empty and one below zero proven solid,
+//! which sampling cannot show. The bounds grow with the box size and with repeated variables
+//! (`x * x` on `[-1, 2]` gives `[-2, 4]`), but are never too tight: each rounded operation
+//! moves its bounds outward by one unit in the last place, which covers the rounding of the
+//! arithmetic and `sqrt` and the error of `cos` and `atan2` in common math libraries. Branches
+//! taken on [`Scalar::value`] use the midpoint and are only safe on quantities that do not
+//! depend on the point.
+
+use std::f64::consts::{FRAC_PI_2, PI, TAU};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use cgmath::Vector3;
+
+use crate::scalar::Scalar;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Interval {
+    pub lo: f64,
+    pub hi: f64,
+}
+
+impl Interval {
+    pub fn new(lo: f64, hi: f64) -> Self {
+        Self { lo, hi }
+    }
+
+    pub fn point(value: f64) -> Self {
+        Self::new(value, value)
+    }
+
+    pub fn entire() -> Self {
+        Self::new(f64::NEG_INFINITY, f64::INFINITY)
+    }
+
+    /// The coordinates over the box from `lo` to `hi`.
+    pub fn from_box(lo: Vector3<f32>, hi: Vector3<f32>) -> Vector3<Interval> {
+        Vector3::new(
+            Self::new(lo.x as f64, hi.x as f64),
+            Self::new(lo.y as f64, hi.y as f64),
+            Self::new(lo.z as f64, hi.z as f64),
+        )
+    }
+
+    pub fn width(self) -> f64 {
+        self.hi - self.lo
+    }
+
+    pub fn mid(self) -> f64 {
+        (self.lo + self.hi) / 2.0
+    }
+
+    pub fn contains(self, value: f64) -> bool {
+        self.lo <= value && value <= self.hi
+    }
+
+    /// The interval widened by one unit in the last place on each side, for bounds that were
+    /// rounded to nearest.
+    fn round_out(self) -> Self {
+        Self::new(self.lo.next_down(), self.hi.next_up())
+    }
+
+    /// Smallest interval holding all of `values`, rounded out, the whole line if any is NaN.
+    fn hull(values: [f64; 4]) -> Self {
+        if values.iter().any(|v| v.is_nan()) {
+            return Self::entire();
+        }
+        Self::new(values.into_iter().fold(f64::INFINITY, f64::min), values.into_iter().fold(f64::NEG_INFINITY, f64::max))
+            .round_out()
+    }
+
+    pub fn abs(self) -> Self {
+        if self.lo >= 0.0 {
+            self
+        } else if self.hi <= 0.0 {
+            -self
+        } else {
+            Self::new(0.0, (-self.lo).max(self.hi))
+        }
+    }
+
+    /// Square root of the non-negative part.
+    pub fn sqrt(self) -> Self {
+        Self::new(self.lo.max(0.0).sqrt(), self.hi.max(0.0).sqrt()).round_out()
+    }
+
+    pub fn cos(self) -> Self {
+        if self.width().is_nan() || self.width() >= TAU {
+            return Self::new(-1.0, 1.0);
+        }
+        let (a, b) = (self.lo.cos(), self.hi.cos());
+        let (mut lo, mut hi) = (a.min(b), a.max(b));
+        // Maxima at even and minima at odd multiples of pi inside the interval
+        let first = (self.lo / PI).ceil();
+        let mut k = first;
+        while k * PI <= self.hi {
+            if (k as i64).rem_euclid(2) == 0 {
+                hi = 1.0;
+            } else {
+                lo = -1.0;
+            }
+            k += 1.0;
+        }
+        Self::new(lo, hi).round_out()
+    }
+
+    pub fn sin(self) -> Self {
+        (self - Self::point(FRAC_PI_2)).cos()
+    }
+
+    /// Bounds of the angle over all points of the box, the full circle if it touches the
+    /// negative x-axis where the angle jumps.
+    pub fn atan2(self, other: Self) -> Self {
+        if self.contains(0.0) && other.lo <= 0.0 {
+            return Self::new(-PI, PI).round_out();
+        }
+        // Monotonic in each coordinate away from the cut, so the corners are the extremes
+        Self::hull([
+            self.lo.atan2(other.lo),
+            self.lo.atan2(other.hi),
+            self.hi.atan2(other.lo),
+            self.hi.atan2(other.hi),
+        ])
+    }
+
+    pub fn min(self, other: Self) -> Self {
+        Self::new(self.lo.min(other.lo), self.hi.min(other.hi))
+    }
+
+    pub fn max(self, other: Self) -> Self {
+        Self::new(self.lo.max(other.lo), self.hi.max(other.hi))
+    }
+
+    /// What a distance interval proves about its box.
+    pub fn classify(self) -> BoxClass {
+        if self.lo > 0.0 {
+            BoxClass::Outside
+        } else if self.hi < 0.0 {
+            BoxClass::Inside
+        } else {
+            BoxClass::Boundary
+        }
+    }
+}
+
+impl Scalar for Interval {
+    fn from_f64(value: f64) -> Self {
+        Self::point(value)
+    }
+    fn value(self) -> f64 {
+        self.mid()
+    }
+    fn abs(self) -> Self {
+        Interval::abs(self)
+    }
+    fn sqrt(self) -> Self {
+        Interval::sqrt(self)
+    }
+    fn sin(self) -> Self {
+        Interval::sin(self)
+    }
+    fn cos(self) -> Self {
+        Interval::cos(self)
+    }
+    fn atan2(self, other: Self) -> Self {
+        Interval::atan2(self, other)
+    }
+    fn min(self, other: Self) -> Self {
+        Interval::min(self, other)
+    }
+    fn max(self, other: Self) -> Self {
+        Interval::max(self, other)
+    }
+}
+
+impl Neg for Interval {
+    type Output = Interval;
+    fn neg(self) -> Interval {
+        Interval::new(-self.hi, -self.lo)
+    }
+}
+
+impl Add for Interval {
+    type Output = Interval;
+    fn add(self, other: Interval) -> Interval {
+        Interval::new(self.lo + other.lo, self.hi + other.hi).round_out()
+    }
+}
+
+impl Sub for Interval {
+    type Output = Interval;
+    fn sub(self, other: Interval) -> Interval {
+        Interval::new(self.lo - other.hi, self.hi - other.lo).round_out()
+    }
+}
+
+impl Mul for Interval {
+    type Output = Interval;
+    fn mul(self, other: Interval) -> Interval {
+        Interval::hull([self.lo * other.lo, self.lo * other.hi, self.hi * other.lo, self.hi * other.hi])
+    }
+}
+
+impl Div for Interval {
+    type Output = Interval;
+    fn div(self, other: Interval) -> Interval {
+        if other.contains(0.0) {
+            return Interval::entire();
+        }
+        self * Interval::new(1.0 / other.hi, 1.0 / other.lo).round_out()
+    }
+}
+
+/// Mixed arithmetic with plain numbers, as for `Dual`.
+macro_rules! impl_interval_f64_op {
+    ($op:ident, $method:ident) => {
+        impl $op<f64> for Interval {
+            type Output = Interval;
+            fn $method(self, other: f64) -> Interval {
+                $op::$method(self, Interval::point(other))
+            }
+        }
+
+        impl $op<Interval> for f64 {
+            type Output = Interval;
+            fn $method(self, other: Interval) -> Interval {
+                $op::$method(Interval::point(self), other)
+            }
+        }
+    };
+}
+
+impl_interval_f64_op!(Add, add);
+impl_interval_f64_op!(Sub, sub);
+impl_interval_f64_op!(Mul, mul);
+impl_interval_f64_op!(Div, div);
+
+/// What the distance interval over a box proves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoxClass {
+    /// The distance is positive everywhere, the box is empty.
+    Outside,
+    /// The distance is negative everywhere, the box is solid.
+    Inside,
+    /// May contain the surface.
+    Boundary,
+}
+
+/// Boxes of an octree subdivision by what they were proven to be.
+#[derive(Debug, Clone, Default)]
+pub struct Partition {
+    pub inside: Vec<[Vector3<f32>; 2]>,
+    /// Boxes at the finest level that were not proven either way.
+    pub boundary: Vec<[Vector3<f32>; 2]>,
+    /// Interval evaluations, one per visited box.
+    pub evaluations: usize,
+}
+
+fn volume([lo, hi]: &[Vector3<f32>; 2]) -> f64 {
+    let size = hi - lo;
+    size.x as f64 * size.y as f64 * size.z as f64
+}
+
+impl Partition {
+    /// Bounding box of the boxes that may hold solid, `None` if the region is proven empty.
+    pub fn enclosure(&self) -> Option<[Vector3<f32>; 2]> {
+        self.inside.iter().chain(&self.boundary).copied().reduce(|[lo, hi], [a, b]| {
+            [
+                Vector3::new(lo.x.min(a.x), lo.y.min(a.y), lo.z.min(a.z)),
+                Vector3::new(hi.x.max(b.x), hi.y.max(b.y), hi.z.max(b.z)),
+            ]
+        })
+    }
+
+    /// Lower and upper bound of the solid volume.
+    pub fn volume_bounds(&self) -> (f64, f64) {
+        let inside: f64 = self.inside.iter().map(volume).sum();
+        (inside, inside + self.boundary.iter().map(volume).sum::<f64>())
+    }
+}
+
+/// Splits `bbox` into octants up to `depth` times, keeping boxes the interval of `distance`
+/// proves solid and dropping those it proves empty. Only boundary boxes are split further.
+pub fn partition(distance: impl Fn(Vector3<Interval>) -> Interval, bbox: [Vector3<f32>; 2], depth: usize) -> Partition {
+    let mut partition = Partition::default();
+    let mut stack = vec![(bbox, depth)];
+    while let Some(([lo, hi], depth)) = stack.pop() {
+        partition.evaluations += 1;
+        match distance(Interval::from_box(lo, hi)).classify() {
+            BoxClass::Outside => {}
+            BoxClass::Inside => partition.inside.push([lo, hi]),
+            BoxClass::Boundary if depth == 0 => partition.boundary.push([lo, hi]),
+            BoxClass::Boundary => {
+                let mid = (lo + hi) / 2.0;
+                for i in 0..8 {
+                    let pick = |bit: usize, l: f32, m: f32, h: f32| if i & bit == 0 { [l, m] } else { [m, h] };
+                    let [x0, x1] = pick(1, lo.x, mid.x, hi.x);
+                    let [y0, y1] = pick(2, lo.y, mid.y, hi.y);
+                    let [z0, z1] = pick(4, lo.z, mid.z, hi.z);
+                    stack.push(([Vector3::new(x0, y0, z0), Vector3::new(x1, y1, z1)], depth - 1));
+                }
+            }
+        }
+    }
+    partition
+}
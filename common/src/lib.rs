@@ -1,10 +1,62 @@
 // Common utilities and helpers for all press objects
 
 pub use cgmath::{Vector3, Matrix3, Rad, InnerSpace, Matrix};
+pub use scalar::Scalar;
+
+use scalar::{add, cross, dot, magnitude, normalize, scale, sub};
+
+pub mod batch;
+pub mod bench;
+pub mod bounds;
+pub mod bvh;
+pub mod clearance;
+pub mod dual;
+pub mod golden;
+pub mod interval;
+pub mod mass;
+pub mod mesh;
+pub mod outline;
+pub mod part;
+pub mod print;
+pub mod scalar;
+pub mod slice;
+pub mod thickness;
+pub mod validate;
 
 /// Macro to create computation structs with lazy evaluated fields
+///
+/// The coordinates are `f64`, or any [`Scalar`] with `create_computation! { Config, S: Scalar; ... }`.
+/// The struct is then generic over `S`, so the fields can be evaluated on `f64` for sampling,
+/// on [`dual::Dual`] for the exact gradient or on [`interval::Interval`] for bounds over a box.
 #[macro_export]
 macro_rules! create_computation {
+    ($cfg:ty, $s:ident: Scalar; $( $field:ident: $type:ty => $calc:expr ),* $(,)?) => {
+        struct Computation<'a, $s: $crate::scalar::Scalar> {
+            cfg: &'a $cfg,
+            x: $s,
+            y: $s,
+            z: $s,
+            $( $field: once_cell::unsync::OnceCell<$type>, )*
+        }
+
+        impl<'a, $s: $crate::scalar::Scalar> Computation<'a, $s> {
+            fn new(cfg: &'a $cfg, x: $s, y: $s, z: $s) -> Self {
+                Self {
+                    cfg,
+                    x,
+                    y,
+                    z,
+                    $( $field: once_cell::unsync::OnceCell::new(), )*
+                }
+            }
+
+            $(
+                fn $field(&self) -> $type {
+                    *self.$field.get_or_init(|| $calc(self))
+                }
+            )*
+        }
+    };
     ($cfg:ty, $( $field:ident: $type:ty => $calc:expr ),* $(,)?) => {
         struct Computation<'a> {
             cfg: &'a $cfg,
@@ -49,8 +101,8 @@ macro_rules! create_computation {
 /// let translated_p = translate(p, Vector3::new(1.0, 2.0, 3.0));
 /// let distance = sphere(translated_p);
 /// ```
-pub fn translate(p: Vector3<f32>, offset: Vector3<f32>) -> Vector3<f32> {
-    p - offset
+pub fn translate<S: Scalar>(p: Vector3<S>, offset: Vector3<S>) -> Vector3<S> {
+    sub(p, offset)
 }
 
 /// Rotate a point around an axis.
@@ -68,17 +120,17 @@ pub fn translate(p: Vector3<f32>, offset: Vector3<f32>) -> Vector3<f32> {
 /// let rotated_p = rotate_axis(p, Vector3::new(0.0, 0.0, 1.0), std::f32::consts::PI / 2.0);
 /// let distance = shape(rotated_p);
 /// ```
-pub fn rotate_axis(p: Vector3<f32>, axis: Vector3<f32>, angle: f32) -> Vector3<f32> {
-    let axis = axis.normalize();
+pub fn rotate_axis<S: Scalar>(p: Vector3<S>, axis: Vector3<S>, angle: S) -> Vector3<S> {
+    let axis = normalize(axis);
     let cos_a = (-angle).cos(); // Inverse rotation: negate angle
     let sin_a = (-angle).sin();
-    let one_minus_cos = 1.0 - cos_a;
+    let one_minus_cos = S::from_f64(1.0) - cos_a;
 
     // Rodrigues' rotation formula (inverse rotation)
-    let cross = axis.cross(p);
-    let dot = axis.dot(p);
+    let cross = cross(axis, p);
+    let dot = dot(axis, p);
 
-    p * cos_a + cross * sin_a + axis * (dot * one_minus_cos)
+    add(add(scale(p, cos_a), scale(cross, sin_a)), scale(axis, dot * one_minus_cos))
 }
 
 /// Rotate a point using Euler angles (ZYX order).
@@ -97,11 +149,11 @@ pub fn rotate_axis(p: Vector3<f32>, axis: Vector3<f32>, angle: f32) -> Vector3<f
 /// let rotated_p = rotate_euler(p, 0.0, std::f32::consts::PI / 4.0, 0.0);
 /// let distance = shape(rotated_p);
 /// ```
-pub fn rotate_euler(p: Vector3<f32>, pitch: f32, yaw: f32, roll: f32) -> Vector3<f32> {
+pub fn rotate_euler<S: Scalar>(p: Vector3<S>, pitch: S, yaw: S, roll: S) -> Vector3<S> {
     // Build rotation matrix (inverse rotation: negate angles)
-    let (sp, cp) = (-pitch).sin_cos();
-    let (sy, cy) = (-yaw).sin_cos();
-    let (sr, cr) = (-roll).sin_cos();
+    let (sp, cp) = ((-pitch).sin(), (-pitch).cos());
+    let (sy, cy) = ((-yaw).sin(), (-yaw).cos());
+    let (sr, cr) = ((-roll).sin(), (-roll).cos());
 
     // Rotation matrix (ZYX order, inverse)
     let m = Matrix3::new(
@@ -110,7 +162,8 @@ pub fn rotate_euler(p: Vector3<f32>, pitch: f32, yaw: f32, roll: f32) -> Vector3
         -cp * sy * cr + sp * sr, cp * sy * sr + sp * cr, cp * cy,
     );
 
-    m * p
+    // Columns times coordinates, as `m * p`
+    add(add(scale(m.x, p.x), scale(m.y, p.y)), scale(m.z, p.z))
 }
 
 /// Rotate a point using a rotation matrix.
@@ -124,23 +177,23 @@ pub fn rotate_euler(p: Vector3<f32>, pitch: f32, yaw: f32, roll: f32) -> Vector3
 /// let rotated_p = rotate_matrix(p, rotation);
 /// let distance = shape(rotated_p);
 /// ```
-pub fn rotate_matrix(p: Vector3<f32>, rotation: Matrix3<f32>) -> Vector3<f32> {
-    rotation.transpose() * p
+pub fn rotate_matrix<S: Scalar>(p: Vector3<S>, rotation: Matrix3<S>) -> Vector3<S> {
+    Vector3::new(dot(rotation.x, p), dot(rotation.y, p), dot(rotation.z, p))
 }
 
 /// Rotate a point around the X-axis.
-pub fn rotate_x(p: Vector3<f32>, angle: f32) -> Vector3<f32> {
-    rotate_axis(p, Vector3::new(1.0, 0.0, 0.0), angle)
+pub fn rotate_x<S: Scalar>(p: Vector3<S>, angle: S) -> Vector3<S> {
+    rotate_axis(p, Vector3::new(S::from_f64(1.0), S::from_f64(0.0), S::from_f64(0.0)), angle)
 }
 
 /// Rotate a point around the Y-axis.
-pub fn rotate_y(p: Vector3<f32>, angle: f32) -> Vector3<f32> {
-    rotate_axis(p, Vector3::new(0.0, 1.0, 0.0), angle)
+pub fn rotate_y<S: Scalar>(p: Vector3<S>, angle: S) -> Vector3<S> {
+    rotate_axis(p, Vector3::new(S::from_f64(0.0), S::from_f64(1.0), S::from_f64(0.0)), angle)
 }
 
 /// Rotate a point around the Z-axis.
-pub fn rotate_z(p: Vector3<f32>, angle: f32) -> Vector3<f32> {
-    rotate_axis(p, Vector3::new(0.0, 0.0, 1.0), angle)
+pub fn rotate_z<S: Scalar>(p: Vector3<S>, angle: S) -> Vector3<S> {
+    rotate_axis(p, Vector3::new(S::from_f64(0.0), S::from_f64(0.0), S::from_f64(1.0)), angle)
 }
 
 /// Calculate the signed distance to a cylinder between two points.
@@ -159,22 +212,22 @@ pub fn rotate_z(p: Vector3<f32>, angle: f32) -> Vector3<f32> {
 /// let b = Vector3::new(0.0, 0.0, 5.0);
 /// let distance = cylinder_between(p, a, b, 0.5);
 /// ```
-pub fn cylinder_between(p: Vector3<f32>, a: Vector3<f32>, b: Vector3<f32>, radius: f32) -> f32 {
-    let ab = b - a;
-    let ap = p - a;
+pub fn cylinder_between<S: Scalar>(p: Vector3<S>, a: Vector3<S>, b: Vector3<S>, radius: S) -> S {
+    let ab = sub(b, a);
+    let ap = sub(p, a);
 
     // Project ap onto ab
-    let ab_len_sq = ab.dot(ab);
-    if ab_len_sq < 1e-6 {
+    let ab_len_sq = dot(ab, ab);
+    if ab_len_sq.value() < 1e-6 {
         // Degenerate case: a and b are the same point, treat as sphere
-        return (p - a).magnitude() - radius;
+        return magnitude(sub(p, a)) - radius;
     }
 
-    let t = (ap.dot(ab) / ab_len_sq).max(0.0).min(1.0);
+    let t = (dot(ap, ab) / ab_len_sq).max(S::from_f64(0.0)).min(S::from_f64(1.0));
 
     // Closest point on the line segment
-    let q = a + ab * t;
+    let q = add(a, scale(ab, t));
 
     // Distance from p to the line segment, minus radius
-    (p - q).magnitude() - radius
+    magnitude(sub(p, q)) - radius
 }
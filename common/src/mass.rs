@@ -0,0 +1,223 @@
+//! Volume, surface area, center of mass and inertia of the negative region of an SDF.
+//!
+//! The bounding box is covered by cubes that are refined level by level. Cubes whose center
+//! distance proves them fully inside or outside are integrated exactly, the remaining boundary
+//! cubes are subdivided until the volume estimate converges to the requested accuracy.
+
+use std::fmt;
+
+use cgmath::{InnerSpace, Matrix3, SquareMatrix, Vector3, Zero};
+use sdf_viewer::sdf::SDFSurface;
+
+/// Parameters of [`mass_properties`].
+#[derive(Debug, Clone)]
+pub struct MassConfig {
+    /// Requested volume accuracy relative to the volume, refinement stops once the estimate
+    /// changes by less than this between levels.
+    pub tolerance: f64,
+    /// Assumed Lipschitz constant of the distance field, see `validate::check_lipschitz`.
+    pub lipschitz: f32,
+    /// Cubes along the longest bounding box side on the first level.
+    pub initial_cells: usize,
+    /// Maximum number of refinements of the initial cubes.
+    pub max_depth: usize,
+}
+
+impl Default for MassConfig {
+    fn default() -> Self {
+        Self {
+            tolerance: 1e-3,
+            lipschitz: 1.5,
+            initial_cells: 16,
+            max_depth: 8,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MassProperties {
+    pub volume: f64,
+    /// Change of the volume estimate in the last refinement, an estimate of its error.
+    pub volume_error: f64,
+    pub area: f64,
+    pub density: f64,
+    pub mass: f64,
+    pub center_of_mass: Vector3<f64>,
+    /// Inertia tensor about the center of mass.
+    pub inertia: Matrix3<f64>,
+}
+
+impl fmt::Display for MassProperties {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let c = self.center_of_mass;
+        let i = self.inertia;
+        writeln!(f, "volume: {:.6} +- {:.6}", self.volume, self.volume_error)?;
+        writeln!(f, "area: {:.6}", self.area)?;
+        writeln!(f, "mass: {:.6} (density {})", self.mass, self.density)?;
+        writeln!(f, "center of mass: ({:.6}, {:.6}, {:.6})", c.x, c.y, c.z)?;
+        writeln!(f, "inertia: [{:.6e}, {:.6e}, {:.6e}]", i.x.x, i.y.x, i.z.x)?;
+        writeln!(f, "         [{:.6e}, {:.6e}, {:.6e}]", i.x.y, i.y.y, i.z.y)?;
+        writeln!(f, "         [{:.6e}, {:.6e}, {:.6e}]", i.x.z, i.y.z, i.z.z)
+    }
+}
+
+/// Volume moments: `∫ 1`, `∫ x` and `∫ x xᵀ` over a region.
+#[derive(Debug, Clone, Copy)]
+struct Moments {
+    volume: f64,
+    first: Vector3<f64>,
+    second: Matrix3<f64>,
+}
+
+impl Moments {
+    fn zero() -> Self {
+        Self { volume: 0.0, first: Vector3::zero(), second: Matrix3::zero() }
+    }
+
+    /// Add `fraction` of the moments of the cube at `center` with edge `edge`.
+    fn add_cube(&mut self, center: Vector3<f64>, edge: f64, fraction: f64) {
+        let v = edge.powi(3) * fraction;
+        let var = edge * edge / 12.0;
+        let c = center;
+        self.volume += v;
+        self.first += c * v;
+        self.second += Matrix3::new(
+            c.x * c.x + var, c.x * c.y, c.x * c.z,
+            c.y * c.x, c.y * c.y + var, c.y * c.z,
+            c.z * c.x, c.z * c.y, c.z * c.z + var,
+        ) * v;
+    }
+}
+
+/// Integrate the negative region of `surface` with the given `density`.
+pub fn mass_properties<S: SDFSurface + ?Sized>(
+    surface: &S,
+    density: f64,
+    cfg: &MassConfig,
+) -> MassProperties {
+    let [lo, hi] = surface.bounding_box();
+    let size = hi - lo;
+    let mut edge = size.x.max(size.y).max(size.z) / cfg.initial_cells as f32;
+    let counts = [size.x, size.y, size.z].map(|s| ((s / edge).ceil() as usize).max(1));
+
+    let mut cells = vec![];
+    for k in 0..counts[2] {
+        for j in 0..counts[1] {
+            for i in 0..counts[0] {
+                cells.push(lo + Vector3::new(i as f32 + 0.5, j as f32 + 0.5, k as f32 + 0.5) * edge);
+            }
+        }
+    }
+
+    let mut moments = Moments::zero();
+    let mut previous: Option<f64> = None;
+    let mut depth = 0;
+    loop {
+        let half_diagonal = edge * 3f32.sqrt() / 2.0;
+        let cube = (edge as f64).powi(3);
+        let mut boundary = vec![];
+        let mut partial = 0.0;
+        for center in cells {
+            let d = surface.sample(center, true).distance;
+            if d < -cfg.lipschitz * half_diagonal {
+                moments.add_cube(center.cast().unwrap(), edge as f64, 1.0);
+            } else if d <= cfg.lipschitz * half_diagonal {
+                boundary.push((center, d));
+                partial += fraction(d, edge) * cube;
+            }
+        }
+
+        // Stop when refining no longer changes the estimate by more than the tolerance
+        let estimate = moments.volume + partial;
+        let change = previous.map(|p| (estimate - p).abs());
+        if depth == cfg.max_depth || change.is_some_and(|c| c <= cfg.tolerance * estimate) {
+            let mut area_cells = 0;
+            for &(center, d) in &boundary {
+                moments.add_cube(center.cast().unwrap(), edge as f64, fraction(d, edge));
+                if d.abs() < edge / 2.0 {
+                    area_cells += 1;
+                }
+            }
+            // The shell |d| < edge / 2 has volume area * edge, each center in it stands for edge^3
+            let area = area_cells as f64 * (edge as f64).powi(2);
+            return finish(moments, change.unwrap_or(partial), area, density);
+        }
+        previous = Some(estimate);
+
+        let quarter = edge / 4.0;
+        cells = Vec::with_capacity(boundary.len() * 8);
+        for (center, _) in boundary {
+            for octant in 0..8 {
+                let offset = Vector3::new(
+                    if octant & 1 == 0 { -quarter } else { quarter },
+                    if octant & 2 == 0 { -quarter } else { quarter },
+                    if octant & 4 == 0 { -quarter } else { quarter },
+                );
+                cells.push(center + offset);
+            }
+        }
+        edge /= 2.0;
+        depth += 1;
+    }
+}
+
+/// Inside fraction of a boundary cube, exact for surfaces parallel to a cube face.
+fn fraction(d: f32, edge: f32) -> f64 {
+    (0.5 - d / edge).clamp(0.0, 1.0) as f64
+}
+
+fn finish(moments: Moments, volume_error: f64, area: f64, density: f64) -> MassProperties {
+    let volume = moments.volume;
+    let center_of_mass = if volume > 0.0 { moments.first / volume } else { Vector3::zero() };
+    // Central second moment S = ∫ (x - c)(x - c)ᵀ, inertia I = ρ (tr(S) E - S)
+    let c = center_of_mass;
+    let central = moments.second
+        - Matrix3::new(
+            c.x * c.x, c.x * c.y, c.x * c.z,
+            c.y * c.x, c.y * c.y, c.y * c.z,
+            c.z * c.x, c.z * c.y, c.z * c.z,
+        ) * volume;
+    let trace = central.x.x + central.y.y + central.z.z;
+    let inertia = (Matrix3::from_value(trace) - central) * density;
+    MassProperties {
+        volume,
+        volume_error,
+        area,
+        density,
+        mass: volume * density,
+        center_of_mass,
+        inertia,
+    }
+}
+
+/// Mass properties of several bodies as one, e.g. the materials of a multi-material object.
+pub fn combine(parts: &[MassProperties]) -> MassProperties {
+    let mass: f64 = parts.iter().map(|p| p.mass).sum();
+    let volume: f64 = parts.iter().map(|p| p.volume).sum();
+    let center_of_mass = if mass > 0.0 {
+        parts.iter().map(|p| p.center_of_mass * p.mass).fold(Vector3::zero(), |a, b| a + b)
+            / mass
+    } else {
+        Vector3::zero()
+    };
+    // Parallel axis theorem: I = I_c + m (|r|² E - r rᵀ)
+    let mut inertia = Matrix3::zero();
+    for p in parts {
+        let r = p.center_of_mass - center_of_mass;
+        let outer = Matrix3::new(
+            r.x * r.x, r.x * r.y, r.x * r.z,
+            r.y * r.x, r.y * r.y, r.y * r.z,
+            r.z * r.x, r.z * r.y, r.z * r.z,
+        );
+        inertia += p.inertia + (Matrix3::from_value(r.magnitude2()) - outer) * p.mass;
+    }
+    MassProperties {
+        volume,
+        volume_error: parts.iter().map(|p| p.volume_error).sum(),
+        area: parts.iter().map(|p| p.area).sum(),
+        density: if volume > 0.0 { mass / volume } else { 0.0 },
+        mass,
+        center_of_mass,
+        inertia,
+    }
+}
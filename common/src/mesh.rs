@@ -0,0 +1,287 @@
+//! Triangle meshes of SDF surfaces and multi-body export.
+//!
+//! Meshes are extracted with marching tetrahedra on a regular grid over the bounding box.
+//! The grid reaches one cell past the box and its outer layer counts as outside, so every
+//! mesh is closed even where the box clips the surface. Several meshes can be written as
+//! one glTF file with a named, coloured object per body, or each as its own PLY file.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use cgmath::{InnerSpace, Vector3};
+use sdf_viewer::sdf::SDFSurface;
+
+/// Parameters of [`Mesh::extract`].
+#[derive(Debug, Clone)]
+pub struct MeshConfig {
+    /// Grid cells along the longest bounding box side.
+    pub resolution: usize,
+}
+
+impl Default for MeshConfig {
+    fn default() -> Self {
+        Self { resolution: 64 }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Mesh {
+    pub positions: Vec<Vector3<f32>>,
+    /// Counter-clockwise seen from outside.
+    pub triangles: Vec<[u32; 3]>,
+}
+
+/// Kuhn triangulation of the cube around the 0-7 diagonal, corners indexed by `x | y << 1 | z << 2`.
+/// Neighbouring cubes split their shared faces the same way, so the surface has no cracks.
+const TETRAHEDRA: [[usize; 4]; 6] = [
+    [0, 1, 3, 7],
+    [0, 3, 2, 7],
+    [0, 2, 6, 7],
+    [0, 6, 4, 7],
+    [0, 4, 5, 7],
+    [0, 5, 1, 7],
+];
+
+impl Mesh {
+    /// Mesh the zero level set of `surface` within its bounding box.
+    pub fn extract<S: SDFSurface + ?Sized>(surface: &S, cfg: &MeshConfig) -> Self {
+        let [lo, hi] = surface.bounding_box();
+        let size = hi - lo;
+        let cell = size.x.max(size.y).max(size.z) / cfg.resolution as f32;
+        // One padding cell on each side
+        let origin = lo - Vector3::new(cell, cell, cell);
+        let counts = [size.x, size.y, size.z].map(|s| (s / cell).ceil() as usize + 3);
+        let index = |i: usize, j: usize, k: usize| i + counts[0] * (j + counts[1] * k);
+        let point = |i: usize, j: usize, k: usize| origin + Vector3::new(i as f32, j as f32, k as f32) * cell;
+
+        let mut values = Vec::with_capacity(counts[0] * counts[1] * counts[2]);
+        for k in 0..counts[2] {
+            for j in 0..counts[1] {
+                for i in 0..counts[0] {
+                    let boundary = i == 0 || j == 0 || k == 0
+                        || i == counts[0] - 1 || j == counts[1] - 1 || k == counts[2] - 1;
+                    let d = surface.sample(point(i, j, k), true).distance;
+                    values.push(if boundary { d.max(cell * 1e-3) } else { d });
+                }
+            }
+        }
+
+        let mut mesh = Mesh::default();
+        let mut edge_vertices: HashMap<(usize, usize), u32> = HashMap::new();
+        for k in 0..counts[2] - 1 {
+            for j in 0..counts[1] - 1 {
+                for i in 0..counts[0] - 1 {
+                    let corners = [0, 1, 2, 3, 4, 5, 6, 7]
+                        .map(|c| index(i + (c & 1), j + ((c >> 1) & 1), k + ((c >> 2) & 1)));
+                    let inside = corners.map(|c| values[c] < 0.0);
+                    if inside.iter().all(|&v| v) || inside.iter().all(|&v| !v) {
+                        continue;
+                    }
+                    for tet in TETRAHEDRA {
+                        let tet = tet.map(|c| corners[c]);
+                        mesh.add_tetrahedron(tet, &values, &mut edge_vertices, |c| {
+                            point(c % counts[0], c / counts[0] % counts[1], c / (counts[0] * counts[1]))
+                        });
+                    }
+                }
+            }
+        }
+        mesh
+    }
+
+    /// Triangulate the zero crossing inside one tetrahedron of grid points.
+    fn add_tetrahedron(
+        &mut self,
+        tet: [usize; 4],
+        values: &[f32],
+        edge_vertices: &mut HashMap<(usize, usize), u32>,
+        point: impl Fn(usize) -> Vector3<f32>,
+    ) {
+        let (inside, outside): (Vec<usize>, Vec<usize>) = tet.iter().partition(|&&c| values[c] < 0.0);
+        if inside.is_empty() || outside.is_empty() {
+            return;
+        }
+        let mut vertex = |a: usize, b: usize| {
+            let key = (a.min(b), a.max(b));
+            *edge_vertices.entry(key).or_insert_with(|| {
+                let (va, vb) = (values[a], values[b]);
+                let t = va / (va - vb);
+                self.positions.push(point(a) + (point(b) - point(a)) * t);
+                (self.positions.len() - 1) as u32
+            })
+        };
+        let polygon = match (inside.len(), outside.len()) {
+            (1, 3) => outside.iter().map(|&o| vertex(inside[0], o)).collect::<Vec<_>>(),
+            (3, 1) => inside.iter().map(|&i| vertex(i, outside[0])).collect(),
+            _ => vec![
+                vertex(inside[0], outside[0]),
+                vertex(inside[0], outside[1]),
+                vertex(inside[1], outside[1]),
+                vertex(inside[1], outside[0]),
+            ],
+        };
+
+        // Orient the triangles away from the inside corners
+        let centroid = |corners: &[usize]| {
+            corners.iter().fold(Vector3::new(0.0, 0.0, 0.0), |s, &c| s + point(c)) / corners.len() as f32
+        };
+        let outward = centroid(&outside) - centroid(&inside);
+        for n in 1..polygon.len() - 1 {
+            let mut triangle = [polygon[0], polygon[n], polygon[n + 1]];
+            let [a, b, c] = triangle.map(|v| self.positions[v as usize]);
+            if (b - a).cross(c - a).dot(outward) < 0.0 {
+                triangle.swap(1, 2);
+            }
+            self.triangles.push(triangle);
+        }
+    }
+
+    pub fn bounds(&self) -> [Vector3<f32>; 2] {
+        let mut lo = Vector3::new(f32::INFINITY, f32::INFINITY, f32::INFINITY);
+        let mut hi = -lo;
+        for p in &self.positions {
+            lo = Vector3::new(lo.x.min(p.x), lo.y.min(p.y), lo.z.min(p.z));
+            hi = Vector3::new(hi.x.max(p.x), hi.y.max(p.y), hi.z.max(p.z));
+        }
+        [lo, hi]
+    }
+
+    /// Write an ASCII PLY file with the given vertex colour.
+    pub fn write_ply(&self, path: impl AsRef<Path>, color: Vector3<f32>) -> io::Result<()> {
+        self.write_ply_colours(path, &vec![color; self.positions.len()])
+    }
+
+    /// Write an ASCII PLY file with a colour per vertex, e.g. an analysis result. Fails without
+    /// writing if `colours` does not have one entry per vertex.
+    pub fn write_ply_colours(&self, path: impl AsRef<Path>, colours: &[Vector3<f32>]) -> io::Result<()> {
+        if colours.len() != self.positions.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} colours for {} vertices", colours.len(), self.positions.len()),
+            ));
+        }
+        let mut out = BufWriter::new(File::create(path)?);
+        writeln!(out, "ply\nformat ascii 1.0")?;
+        writeln!(out, "element vertex {}", self.positions.len())?;
+        writeln!(out, "property float x\nproperty float y\nproperty float z")?;
+        writeln!(out, "property uchar red\nproperty uchar green\nproperty uchar blue")?;
+        writeln!(out, "element face {}", self.triangles.len())?;
+        writeln!(out, "property list uchar uint vertex_indices\nend_header")?;
+        for (p, c) in self.positions.iter().zip(colours) {
+            let [r, g, b] = [c.x, c.y, c.z].map(to_byte);
+            writeln!(out, "{} {} {} {} {} {}", p.x, p.y, p.z, r, g, b)?;
+        }
+        for [a, b, c] in &self.triangles {
+            writeln!(out, "3 {} {} {}", a, b, c)?;
+        }
+        out.flush()
+    }
+}
+
+fn to_byte(c: f32) -> u8 {
+    (c.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+/// A named, coloured mesh of a multi-body export.
+#[derive(Debug, Clone)]
+pub struct Body {
+    pub name: String,
+    pub color: Vector3<f32>,
+    pub mesh: Mesh,
+}
+
+/// Write `bodies` as one glTF 2.0 file with an embedded buffer, one node per body.
+///
+/// Bodies without triangles are skipped.
+pub fn write_gltf(path: impl AsRef<Path>, bodies: &[Body]) -> io::Result<()> {
+    let mut buffer: Vec<u8> = vec![];
+    let (mut nodes, mut meshes, mut materials, mut views, mut accessors) = (vec![], vec![], vec![], vec![], vec![]);
+    for body in bodies.iter().filter(|b| !b.mesh.triangles.is_empty()) {
+        let n = meshes.len();
+        let [lo, hi] = body.mesh.bounds();
+
+        let offset = buffer.len();
+        for p in &body.mesh.positions {
+            for c in [p.x, p.y, p.z] {
+                buffer.extend_from_slice(&c.to_le_bytes());
+            }
+        }
+        views.push(format!(
+            r#"{{"buffer": 0, "byteOffset": {}, "byteLength": {}, "target": 34962}}"#,
+            offset,
+            buffer.len() - offset
+        ));
+        accessors.push(format!(
+            r#"{{"bufferView": {}, "componentType": 5126, "count": {}, "type": "VEC3", "min": [{}, {}, {}], "max": [{}, {}, {}]}}"#,
+            2 * n, body.mesh.positions.len(), lo.x, lo.y, lo.z, hi.x, hi.y, hi.z
+        ));
+
+        let offset = buffer.len();
+        for index in body.mesh.triangles.iter().flatten() {
+            buffer.extend_from_slice(&index.to_le_bytes());
+        }
+        views.push(format!(
+            r#"{{"buffer": 0, "byteOffset": {}, "byteLength": {}, "target": 34963}}"#,
+            offset,
+            buffer.len() - offset
+        ));
+        accessors.push(format!(
+            r#"{{"bufferView": {}, "componentType": 5125, "count": {}, "type": "SCALAR"}}"#,
+            2 * n + 1,
+            body.mesh.triangles.len() * 3
+        ));
+
+        let name = body.name.replace('\\', "\\\\").replace('"', "\\\"");
+        let c = body.color;
+        materials.push(format!(
+            r#"{{"name": "{}", "pbrMetallicRoughness": {{"baseColorFactor": [{}, {}, {}, 1.0], "metallicFactor": 0.0, "roughnessFactor": 1.0}}}}"#,
+            name, c.x, c.y, c.z
+        ));
+        meshes.push(format!(
+            r#"{{"name": "{}", "primitives": [{{"attributes": {{"POSITION": {}}}, "indices": {}, "material": {}}}]}}"#,
+            name, 2 * n, 2 * n + 1, n
+        ));
+        nodes.push(format!(r#"{{"name": "{}", "mesh": {}}}"#, name, n));
+    }
+
+    let list = |items: &[String]| format!("[\n    {}\n  ]", items.join(",\n    "));
+    let scene_nodes = (0..nodes.len()).map(|n| n.to_string()).collect::<Vec<_>>().join(", ");
+    let mut json = String::from("{\n");
+    writeln!(json, r#"  "asset": {{"version": "2.0", "generator": "press-common"}},"#).unwrap();
+    writeln!(json, r#"  "scene": 0,"#).unwrap();
+    writeln!(json, r#"  "scenes": [{{"nodes": [{}]}}],"#, scene_nodes).unwrap();
+    writeln!(json, r#"  "nodes": {},"#, list(&nodes)).unwrap();
+    writeln!(json, r#"  "meshes": {},"#, list(&meshes)).unwrap();
+    writeln!(json, r#"  "materials": {},"#, list(&materials)).unwrap();
+    writeln!(json, r#"  "accessors": {},"#, list(&accessors)).unwrap();
+    writeln!(json, r#"  "bufferViews": {},"#, list(&views)).unwrap();
+    writeln!(
+        json,
+        r#"  "buffers": [{{"byteLength": {}, "uri": "data:application/octet-stream;base64,{}"}}]"#,
+        buffer.len(),
+        base64(&buffer)
+    )
+    .unwrap();
+    json.push_str("}\n");
+    std::fs::write(path, json)
+}
+
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
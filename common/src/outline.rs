@@ -0,0 +1,238 @@
+//! Closed 2D outlines of planar cuts, for flat patterns and 2D CAD export.
+//!
+//! The zero contour of a slice is traced with marching squares, every vertex is then moved
+//! onto the surface along the in-plane distance gradient and the polylines are simplified
+//! within a tolerance. Outlines are written as DXF (R12 polylines) or SVG, one model unit is
+//! one millimetre.
+
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use sdf_viewer::sdf::SDFSurface;
+
+use crate::slice::{Slice, SlicePlane};
+
+/// Parameters of [`Outline::trace`].
+#[derive(Debug, Clone)]
+pub struct OutlineConfig {
+    /// Sampling step of the contour search.
+    pub step: f32,
+    /// Largest distance of a dropped vertex from the simplified outline.
+    pub tolerance: f32,
+}
+
+impl Default for OutlineConfig {
+    fn default() -> Self {
+        Self { step: 0.1, tolerance: 0.01 }
+    }
+}
+
+/// Polylines in plane coordinates, closed loops do not repeat their first point.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Outline {
+    pub polylines: Vec<Polyline>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polyline {
+    pub points: Vec<[f32; 2]>,
+    pub closed: bool,
+}
+
+impl Outline {
+    /// Outline of the cut of `surface` by `plane` within the rectangle `[min, max]`.
+    ///
+    /// Parts reaching the rectangle's border give open polylines.
+    pub fn trace<S: SDFSurface + ?Sized>(
+        surface: &S,
+        plane: SlicePlane,
+        min: [f32; 2],
+        max: [f32; 2],
+        cfg: &OutlineConfig,
+    ) -> Self {
+        let slice = Slice::sample_region(surface, plane, min, max, cfg.step);
+        let polylines = slice
+            .contours()
+            .into_iter()
+            .map(|mut points| {
+                let closed = points.len() > 3 && points.first() == points.last();
+                if closed {
+                    points.pop();
+                }
+                for q in &mut points {
+                    *q = project(surface, plane, *q, cfg.step);
+                }
+                let points = if closed { simplify_closed(&points, cfg.tolerance) } else { simplify(&points, cfg.tolerance) };
+                Polyline { points, closed }
+            })
+            .collect();
+        Self { polylines }
+    }
+
+    /// Smallest rectangle `[min, max]` containing all polylines.
+    pub fn bounds(&self) -> [[f32; 2]; 2] {
+        let mut lo = [f32::INFINITY; 2];
+        let mut hi = [f32::NEG_INFINITY; 2];
+        for p in self.polylines.iter().flat_map(|l| &l.points) {
+            lo = [lo[0].min(p[0]), lo[1].min(p[1])];
+            hi = [hi[0].max(p[0]), hi[1].max(p[1])];
+        }
+        [lo, hi]
+    }
+
+    /// Total length of all polylines, the cutting length of a flat pattern.
+    pub fn length(&self) -> f32 {
+        self.polylines
+            .iter()
+            .map(|l| {
+                let n = l.points.len();
+                let segments = if l.closed { n } else { n.saturating_sub(1) };
+                (0..segments).map(|i| distance(l.points[i], l.points[(i + 1) % n])).sum::<f32>()
+            })
+            .sum()
+    }
+
+    /// DXF R12 with one POLYLINE entity per polyline on `layer`, units in millimetres.
+    pub fn to_dxf(&self, layer: &str) -> String {
+        let mut out = String::new();
+        let mut group = |code: u32, value: &str| writeln!(out, "{}\n{}", code, value).unwrap();
+        group(0, "SECTION");
+        group(2, "HEADER");
+        group(9, "$ACADVER");
+        group(1, "AC1009");
+        group(9, "$INSUNITS");
+        group(70, "4");
+        group(0, "ENDSEC");
+        group(0, "SECTION");
+        group(2, "ENTITIES");
+        for polyline in &self.polylines {
+            group(0, "POLYLINE");
+            group(8, layer);
+            group(66, "1");
+            group(70, if polyline.closed { "1" } else { "0" });
+            for coordinate in [10, 20, 30] {
+                group(coordinate, "0.0");
+            }
+            for p in &polyline.points {
+                group(0, "VERTEX");
+                group(8, layer);
+                group(10, &format!("{:.4}", p[0]));
+                group(20, &format!("{:.4}", p[1]));
+                group(30, "0.0");
+            }
+            group(0, "SEQEND");
+            group(8, layer);
+        }
+        group(0, "ENDSEC");
+        group(0, "EOF");
+        out
+    }
+
+    /// SVG sized in millimetres to the outline's bounds plus `margin`, v pointing up.
+    pub fn to_svg(&self, margin: f32) -> String {
+        let [lo, hi] = self.bounds();
+        let (w, h) = (hi[0] - lo[0] + 2.0 * margin, hi[1] - lo[1] + 2.0 * margin);
+        let mut out = String::new();
+        writeln!(
+            out,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w:.3}mm" height="{h:.3}mm" viewBox="{:.3} {:.3} {w:.3} {h:.3}">"#,
+            lo[0] - margin,
+            -hi[1] - margin,
+        )
+        .unwrap();
+        for polyline in &self.polylines {
+            let mut d = String::new();
+            for (i, p) in polyline.points.iter().enumerate() {
+                write!(d, "{}{:.4},{:.4} ", if i == 0 { "M" } else { "L" }, p[0], -p[1]).unwrap();
+            }
+            if polyline.closed {
+                d.push('Z');
+            }
+            writeln!(out, r#"  <path d="{}" fill="none" stroke="black" stroke-width="0.1"/>"#, d.trim_end()).unwrap();
+        }
+        writeln!(out, "</svg>").unwrap();
+        out
+    }
+
+    pub fn write_dxf(&self, path: impl AsRef<Path>, layer: &str) -> io::Result<()> {
+        fs::write(path, self.to_dxf(layer))
+    }
+
+    pub fn write_svg(&self, path: impl AsRef<Path>, margin: f32) -> io::Result<()> {
+        fs::write(path, self.to_svg(margin))
+    }
+}
+
+/// Move `q` onto the zero level with a few Newton steps along the in-plane gradient.
+fn project<S: SDFSurface + ?Sized>(surface: &S, plane: SlicePlane, q: [f32; 2], step: f32) -> [f32; 2] {
+    let f = |s: f32, t: f32| surface.sample(plane.point(s, t), true).distance;
+    let h = step * 0.05;
+    let mut q = q;
+    for _ in 0..4 {
+        let d = f(q[0], q[1]);
+        let g = [(f(q[0] + h, q[1]) - f(q[0] - h, q[1])) / (2.0 * h), (f(q[0], q[1] + h) - f(q[0], q[1] - h)) / (2.0 * h)];
+        let g2 = g[0] * g[0] + g[1] * g[1];
+        if g2 < 1e-6 {
+            break;
+        }
+        let next = [q[0] - d * g[0] / g2, q[1] - d * g[1] / g2];
+        // A corner or another branch of the surface, keep the marching squares vertex
+        if distance(next, q) > step {
+            break;
+        }
+        q = next;
+    }
+    q
+}
+
+fn distance(a: [f32; 2], b: [f32; 2]) -> f32 {
+    ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2)).sqrt()
+}
+
+/// Distance of `p` from the segment `a`-`b`.
+fn segment_distance(p: [f32; 2], a: [f32; 2], b: [f32; 2]) -> f32 {
+    let ab = [b[0] - a[0], b[1] - a[1]];
+    let len2 = ab[0] * ab[0] + ab[1] * ab[1];
+    let t = if len2 > 0.0 { (((p[0] - a[0]) * ab[0] + (p[1] - a[1]) * ab[1]) / len2).clamp(0.0, 1.0) } else { 0.0 };
+    distance(p, [a[0] + ab[0] * t, a[1] + ab[1] * t])
+}
+
+/// Douglas-Peucker simplification keeping both end points.
+fn simplify(points: &[[f32; 2]], tolerance: f32) -> Vec<[f32; 2]> {
+    if points.len() < 3 {
+        return points.to_vec();
+    }
+    let (a, b) = (points[0], points[points.len() - 1]);
+    let (far, d) = points[1..points.len() - 1]
+        .iter()
+        .enumerate()
+        .map(|(i, &p)| (i + 1, segment_distance(p, a, b)))
+        .fold((0, 0.0), |m, x| if x.1 > m.1 { x } else { m });
+    if d <= tolerance {
+        return vec![a, b];
+    }
+    let mut left = simplify(&points[..=far], tolerance);
+    left.pop();
+    left.extend(simplify(&points[far..], tolerance));
+    left
+}
+
+/// Douglas-Peucker for a closed loop, split at the first point and the point farthest from it.
+fn simplify_closed(points: &[[f32; 2]], tolerance: f32) -> Vec<[f32; 2]> {
+    if points.len() < 4 {
+        return points.to_vec();
+    }
+    let far = (1..points.len())
+        .max_by(|&i, &j| distance(points[0], points[i]).total_cmp(&distance(points[0], points[j])))
+        .unwrap();
+    let mut first = simplify(&points[..=far], tolerance);
+    first.pop();
+    let mut second = points[far..].to_vec();
+    second.push(points[0]);
+    let mut second = simplify(&second, tolerance);
+    second.pop();
+    first.extend(second);
+    first
+}
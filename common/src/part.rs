@@ -0,0 +1,128 @@
+//! Part and material IDs alongside the distance field.
+//!
+//! `SDFSample` only carries a distance and a colour, so objects made of several parts describe
+//! them through the [`Parts`] side channel: the list of parts, the distance field of each part
+//! on its own and the part a point belongs to. Exporters, bills of materials and analyses group
+//! by part ID instead of guessing from colours.
+
+use cgmath::Vector3;
+use sdf_viewer::sdf::{SDFSample, SDFSurface};
+
+use crate::mass::{mass_properties, MassConfig, MassProperties};
+use crate::mesh::{Body, Mesh, MeshConfig};
+use crate::slice::{Slice, SlicePlane};
+
+/// Index into [`Parts::parts`].
+pub type PartId = usize;
+
+/// A material or component of an object.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Part {
+    pub name: String,
+    pub color: Vector3<f32>,
+}
+
+/// An SDF made of separately identifiable parts.
+pub trait Parts: SDFSurface {
+    /// The parts of the object, a part's ID is its index.
+    fn parts(&self) -> Vec<Part>;
+
+    /// Distance to `part` on its own.
+    fn part_distance(&self, part: PartId, p: Vector3<f32>) -> f32;
+
+    /// Distances to all parts at `p`, indexed by part ID.
+    ///
+    /// Override when the parts share work, e.g. one part is cut out of another.
+    fn part_distances(&self, p: Vector3<f32>) -> Vec<f32> {
+        (0..self.parts().len()).map(|i| self.part_distance(i, p)).collect()
+    }
+
+    /// The part `p` belongs to: the part containing it, or else the nearest one.
+    ///
+    /// Override when parts overlap and the object decides which one wins.
+    fn part_at(&self, p: Vector3<f32>) -> PartId {
+        nearest_part(self, p)
+    }
+}
+
+/// The part with the smallest distance at `p`, the default of [`Parts::part_at`].
+pub fn nearest_part<S: Parts + ?Sized>(surface: &S, p: Vector3<f32>) -> PartId {
+    (0..surface.parts().len())
+        .map(|i| (i, surface.part_distance(i, p)))
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map_or(0, |(i, _)| i)
+}
+
+/// A single part of `surface` as an SDF of its own, coloured by the part.
+pub struct PartSurface<'a, S: ?Sized> {
+    pub surface: &'a S,
+    pub part: PartId,
+    color: Vector3<f32>,
+}
+
+impl<'a, S: Parts + ?Sized> PartSurface<'a, S> {
+    pub fn new(surface: &'a S, part: PartId) -> Self {
+        let color = surface.parts()[part].color;
+        Self { surface, part, color }
+    }
+}
+
+impl<S: Parts + ?Sized> SDFSurface for PartSurface<'_, S> {
+    fn bounding_box(&self) -> [Vector3<f32>; 2] {
+        self.surface.bounding_box()
+    }
+
+    fn sample(&self, p: Vector3<f32>, _distance_only: bool) -> SDFSample {
+        SDFSample::new(self.surface.part_distance(self.part, p), self.color)
+    }
+}
+
+/// A closed mesh of each part, named and coloured by the part.
+pub fn part_meshes<S: Parts + ?Sized>(surface: &S, cfg: &MeshConfig) -> Vec<Body> {
+    surface
+        .parts()
+        .into_iter()
+        .enumerate()
+        .map(|(i, part)| Body {
+            mesh: Mesh::extract(&PartSurface::new(surface, i), cfg),
+            name: part.name,
+            color: part.color,
+        })
+        .collect()
+}
+
+/// A slice of each part on the rectangle `[min, max]` of plane coordinates, see
+/// [`Slice::sample_region`]. Samples all parts at once with [`Parts::part_distances`].
+pub fn part_slices<S: Parts + ?Sized>(
+    surface: &S,
+    plane: SlicePlane,
+    min: [f32; 2],
+    max: [f32; 2],
+    step: f32,
+) -> Vec<Slice> {
+    let width = ((max[0] - min[0]) / step).ceil() as usize + 1;
+    let height = ((max[1] - min[1]) / step).ceil() as usize + 1;
+    let mut slices: Vec<Slice> = (0..surface.parts().len())
+        .map(|_| Slice { plane, min, step, width, height, distances: Vec::with_capacity(width * height) })
+        .collect();
+    for j in 0..height {
+        for i in 0..width {
+            let p = plane.point(min[0] + i as f32 * step, min[1] + j as f32 * step);
+            for (slice, d) in slices.iter_mut().zip(surface.part_distances(p)) {
+                slice.distances.push(d);
+            }
+        }
+    }
+    slices
+}
+
+/// Mass properties of each part, `densities` indexed by part ID.
+pub fn part_mass_properties<S: Parts + ?Sized>(
+    surface: &S,
+    densities: &[f64],
+    cfg: &MassConfig,
+) -> Vec<MassProperties> {
+    (0..surface.parts().len())
+        .map(|i| mass_properties(&PartSurface::new(surface, i), densities[i], cfg))
+        .collect()
+}
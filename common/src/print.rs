@@ -0,0 +1,301 @@
+//! Print-bed orientation and overhang analysis of a single part.
+//!
+//! The surface is meshed once and each triangle becomes a sample: its area, its centroid and the
+//! normal from the SDF gradient there. For each candidate "down" direction the samples give the
+//! area hanging over more than the overhang angle, the area resting on the bed and the print
+//! height. Candidates are the 26 directions of a cube's faces, edges and corners plus the normals
+//! of the largest flat faces, so parts with a flat side at an odd angle can lie on it.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use cgmath::{InnerSpace, Matrix, Matrix3, Quaternion, Vector3};
+use sdf_viewer::sdf::{SDFSample, SDFSurface};
+
+use crate::mesh::{Mesh, MeshConfig};
+
+/// Parameters of [`analyze_print`].
+#[derive(Debug, Clone)]
+pub struct PrintConfig {
+    /// Steepest printable overhang in degrees from vertical, downward faces sloping further
+    /// need support.
+    pub overhang_angle: f32,
+    /// Downward faces this close to the bed rest on it instead of overhanging, in mm.
+    pub bed_tolerance: f32,
+    /// Bed contact in mm² a part needs to stand on, orientations with less are only suggested
+    /// when none has more.
+    pub min_contact: f32,
+    /// Overhang areas within this fraction of the surface area of the smallest count as equal,
+    /// the larger bed contact and then the lower height decide between them.
+    pub overhang_tolerance: f32,
+    /// Flat faces whose normals are added as candidates, largest first.
+    pub face_candidates: usize,
+    pub mesh: MeshConfig,
+}
+
+impl Default for PrintConfig {
+    fn default() -> Self {
+        Self {
+            overhang_angle: 45.0,
+            bed_tolerance: 0.2,
+            min_contact: 100.0,
+            overhang_tolerance: 0.01,
+            face_candidates: 8,
+            mesh: MeshConfig { resolution: 96 },
+        }
+    }
+}
+
+/// A patch of the surface.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SurfaceSample {
+    pub point: Vector3<f32>,
+    /// Outward unit normal from the SDF gradient.
+    pub normal: Vector3<f32>,
+    pub area: f32,
+}
+
+/// One candidate orientation of the part.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Orientation {
+    /// Direction of the part that points down onto the bed, a unit vector.
+    pub down: Vector3<f32>,
+    /// Area of downward faces above the bed steeper than the overhang angle, in mm².
+    pub overhang_area: f32,
+    /// Area of downward faces resting on the bed, in mm².
+    pub contact_area: f32,
+    /// Extent on the bed (x, y) and print height (z), in mm.
+    pub size: Vector3<f32>,
+    /// Moves the rotated part onto the bed, centred on the origin.
+    pub offset: Vector3<f32>,
+}
+
+impl Orientation {
+    /// Rotation from part coordinates into print coordinates, taking `down` to -z.
+    pub fn rotation(&self) -> Matrix3<f32> {
+        rotation_to_bed(self.down)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PrintReport {
+    /// Total surface area, in mm².
+    pub area: f32,
+    /// Every candidate, least overhang first.
+    pub candidates: Vec<Orientation>,
+    /// Index of the suggested orientation in `candidates`.
+    pub best: usize,
+    pub overhang_angle: f32,
+}
+
+impl PrintReport {
+    pub fn best(&self) -> &Orientation {
+        &self.candidates[self.best]
+    }
+
+    /// Whether the suggested orientation overhangs by at most `tolerance` of the surface area.
+    pub fn is_support_free(&self, tolerance: f32) -> bool {
+        self.best().overhang_area <= tolerance * self.area
+    }
+}
+
+impl fmt::Display for PrintReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{} orientations, surface {:.0} mm², overhangs beyond {}° from vertical",
+            self.candidates.len(),
+            self.area,
+            self.overhang_angle
+        )?;
+        writeln!(f, "  {:>24} {:>10} {:>10} {:>18}", "down", "overhang", "contact", "size")?;
+        for (i, o) in self.candidates.iter().enumerate() {
+            writeln!(
+                f,
+                "{} ({:>6.3}, {:>6.3}, {:>6.3}) {:>10.1} {:>10.1} {:>5.1} x {:>5.1} x {:>5.1}",
+                if i == self.best { "*" } else { " " },
+                o.down.x,
+                o.down.y,
+                o.down.z,
+                o.overhang_area,
+                o.contact_area,
+                o.size.x,
+                o.size.y,
+                o.size.z
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Normalized central-difference gradient of `surface` at `p` with step `h`, `None` where it
+/// vanishes.
+pub fn gradient<S: SDFSurface + ?Sized>(surface: &S, p: Vector3<f32>, h: f32) -> Option<Vector3<f32>> {
+    let d = |dx, dy, dz| surface.sample(p + Vector3::new(dx, dy, dz), true).distance;
+    let g = Vector3::new(d(h, 0.0, 0.0) - d(-h, 0.0, 0.0), d(0.0, h, 0.0) - d(0.0, -h, 0.0), d(0.0, 0.0, h) - d(0.0, 0.0, -h));
+    (g.magnitude2() > 0.0 && g.magnitude2().is_finite()).then(|| g.normalize())
+}
+
+/// One sample per triangle of the mesh of `surface`, falling back to the triangle normal where
+/// the gradient vanishes.
+pub fn surface_samples<S: SDFSurface + ?Sized>(surface: &S, cfg: &MeshConfig) -> Vec<SurfaceSample> {
+    let mesh = Mesh::extract(surface, cfg);
+    let [lo, hi] = surface.bounding_box();
+    let size = hi - lo;
+    let h = size.x.max(size.y).max(size.z) / cfg.resolution as f32 / 8.0;
+    mesh.triangles
+        .iter()
+        .filter_map(|t| {
+            let [a, b, c] = t.map(|v| mesh.positions[v as usize]);
+            let cross = (b - a).cross(c - a);
+            let area = cross.magnitude() / 2.0;
+            if area <= 0.0 {
+                return None;
+            }
+            let point = (a + b + c) / 3.0;
+            let normal = gradient(surface, point, h).unwrap_or_else(|| cross.normalize());
+            Some(SurfaceSample { point, normal, area })
+        })
+        .collect()
+}
+
+/// The 26 directions of a cube's faces, edges and corners, then the area-weighted normals of the
+/// `faces` largest groups of samples with nearly the same normal.
+pub fn candidate_directions(samples: &[SurfaceSample], faces: usize) -> Vec<Vector3<f32>> {
+    let mut directions = vec![];
+    for d in 0..27 {
+        if d != 13 {
+            let v = Vector3::new((d % 3) as f32 - 1.0, (d / 3 % 3) as f32 - 1.0, (d / 9) as f32 - 1.0);
+            directions.push(v.normalize());
+        }
+    }
+
+    // Normals binned on a grid of about 7° steps
+    let mut groups: HashMap<[i32; 3], (f32, Vector3<f32>)> = HashMap::new();
+    for s in samples {
+        let key = [s.normal.x, s.normal.y, s.normal.z].map(|c| (c * 8.0).round() as i32);
+        let group = groups.entry(key).or_insert((0.0, Vector3::new(0.0, 0.0, 0.0)));
+        group.0 += s.area;
+        group.1 += s.normal * s.area;
+    }
+    let mut groups: Vec<(f32, Vector3<f32>)> = groups.into_values().collect();
+    groups.sort_by(|a, b| b.0.total_cmp(&a.0));
+    for (_, sum) in groups.into_iter().take(faces) {
+        if sum.magnitude2() == 0.0 {
+            continue;
+        }
+        let d = sum.normalize();
+        // Skip directions within about 1° of one already listed
+        if directions.iter().all(|e| e.dot(d) < 0.9998) {
+            directions.push(d);
+        }
+    }
+    directions
+}
+
+/// Overhang, bed contact and size of the part lying with `down` towards the bed.
+pub fn evaluate_orientation(samples: &[SurfaceSample], down: Vector3<f32>, cfg: &PrintConfig) -> Orientation {
+    let rotation = rotation_to_bed(down);
+    let points: Vec<Vector3<f32>> = samples.iter().map(|s| rotation * s.point).collect();
+    let mut lo = Vector3::new(f32::INFINITY, f32::INFINITY, f32::INFINITY);
+    let mut hi = -lo;
+    for p in &points {
+        lo = Vector3::new(lo.x.min(p.x), lo.y.min(p.y), lo.z.min(p.z));
+        hi = Vector3::new(hi.x.max(p.x), hi.y.max(p.y), hi.z.max(p.z));
+    }
+
+    let steep = cfg.overhang_angle.to_radians().sin();
+    let (mut overhang_area, mut contact_area) = (0.0, 0.0);
+    for (s, p) in samples.iter().zip(&points) {
+        if s.normal.dot(down) <= steep {
+            continue;
+        }
+        if p.z - lo.z <= cfg.bed_tolerance {
+            contact_area += s.area;
+        } else {
+            overhang_area += s.area;
+        }
+    }
+    Orientation {
+        down,
+        overhang_area,
+        contact_area,
+        size: hi - lo,
+        offset: Vector3::new(-(lo.x + hi.x) / 2.0, -(lo.y + hi.y) / 2.0, -lo.z),
+    }
+}
+
+/// Evaluate the candidate orientations of `surface` and suggest one: of those standing on at
+/// least `min_contact`, the ones within `overhang_tolerance` of the least overhang, then the
+/// largest bed contact and the lowest height.
+pub fn analyze_print<S: SDFSurface + ?Sized>(surface: &S, cfg: &PrintConfig) -> PrintReport {
+    let samples = surface_samples(surface, &cfg.mesh);
+    let mut candidates: Vec<Orientation> = candidate_directions(&samples, cfg.face_candidates)
+        .into_iter()
+        .map(|down| evaluate_orientation(&samples, down, cfg))
+        .collect();
+    candidates.sort_by(|a, b| a.overhang_area.total_cmp(&b.overhang_area));
+
+    let area: f32 = samples.iter().map(|s| s.area).sum();
+    let min_contact = if candidates.iter().any(|o| o.contact_area >= cfg.min_contact) { cfg.min_contact } else { 0.0 };
+    let stable = |o: &Orientation| o.contact_area >= min_contact;
+    // Sorted by overhang, so the first stable candidate has the least
+    let least = candidates.iter().find(|o| stable(o)).map_or(0.0, |o| o.overhang_area);
+    let best = candidates
+        .iter()
+        .enumerate()
+        .filter(|(_, o)| stable(o) && o.overhang_area <= least + cfg.overhang_tolerance * area)
+        .max_by(|(_, a), (_, b)| {
+            a.contact_area.total_cmp(&b.contact_area).then(b.size.z.total_cmp(&a.size.z))
+        })
+        .map_or(0, |(i, _)| i);
+    PrintReport {
+        area,
+        candidates,
+        best,
+        overhang_angle: cfg.overhang_angle,
+    }
+}
+
+/// Rotation taking `down` to -z.
+fn rotation_to_bed(down: Vector3<f32>) -> Matrix3<f32> {
+    Matrix3::from(Quaternion::from_arc(down, -Vector3::unit_z(), Some(Vector3::unit_x())))
+}
+
+/// A surface turned into a print orientation and placed on the bed at z = 0.
+#[derive(Debug, Clone)]
+pub struct Oriented<S> {
+    pub inner: S,
+    rotation: Matrix3<f32>,
+    offset: Vector3<f32>,
+}
+
+impl<S: SDFSurface> Oriented<S> {
+    pub fn new(inner: S, orientation: &Orientation) -> Self {
+        Self { inner, rotation: orientation.rotation(), offset: orientation.offset }
+    }
+}
+
+impl<S: SDFSurface> SDFSurface for Oriented<S> {
+    fn bounding_box(&self) -> [Vector3<f32>; 2] {
+        let [lo, hi] = self.inner.bounding_box();
+        let mut min = Vector3::new(f32::INFINITY, f32::INFINITY, f32::INFINITY);
+        let mut max = -min;
+        for c in 0..8 {
+            let corner = Vector3::new(
+                if c & 1 == 0 { lo.x } else { hi.x },
+                if c & 2 == 0 { lo.y } else { hi.y },
+                if c & 4 == 0 { lo.z } else { hi.z },
+            );
+            let p = self.rotation * corner + self.offset;
+            min = Vector3::new(min.x.min(p.x), min.y.min(p.y), min.z.min(p.z));
+            max = Vector3::new(max.x.max(p.x), max.y.max(p.y), max.z.max(p.z));
+        }
+        [min, max]
+    }
+
+    fn sample(&self, p: Vector3<f32>, distance_only: bool) -> SDFSample {
+        // Rotations are orthogonal, the transpose is the inverse
+        self.inner.sample(self.rotation.transpose() * (p - self.offset), distance_only)
+    }
+}
@@ -0,0 +1,101 @@
+//! Number types the SDF helpers can be evaluated on.
+//!
+//! Objects sample with `f32` or `f64`. Written against [`Scalar`] instead, the same distance
+//! code also runs on [`crate::dual::Dual`] numbers to get the exact gradient along with the
+//! distance, and on [`crate::interval::Interval`]s to bound it over a box. Constants enter
+//! through [`Scalar::from_f64`], the math functions are named after their `f64` counterparts.
+
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use cgmath::Vector3;
+
+pub trait Scalar:
+    Copy
+    + std::fmt::Debug
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+{
+    fn from_f64(value: f64) -> Self;
+    /// The plain number, dropping anything carried along with it.
+    fn value(self) -> f64;
+    fn abs(self) -> Self;
+    fn sqrt(self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn atan2(self, other: Self) -> Self;
+    fn min(self, other: Self) -> Self;
+    fn max(self, other: Self) -> Self;
+}
+
+macro_rules! impl_scalar_float {
+    ($t:ty) => {
+        impl Scalar for $t {
+            fn from_f64(value: f64) -> Self {
+                value as $t
+            }
+            fn value(self) -> f64 {
+                self as f64
+            }
+            fn abs(self) -> Self {
+                <$t>::abs(self)
+            }
+            fn sqrt(self) -> Self {
+                <$t>::sqrt(self)
+            }
+            fn sin(self) -> Self {
+                <$t>::sin(self)
+            }
+            fn cos(self) -> Self {
+                <$t>::cos(self)
+            }
+            fn atan2(self, other: Self) -> Self {
+                <$t>::atan2(self, other)
+            }
+            fn min(self, other: Self) -> Self {
+                <$t>::min(self, other)
+            }
+            fn max(self, other: Self) -> Self {
+                <$t>::max(self, other)
+            }
+        }
+    };
+}
+
+impl_scalar_float!(f32);
+impl_scalar_float!(f64);
+
+/// `p` with each coordinate converted, e.g. sample points to another [`Scalar`].
+pub fn convert<S: Scalar>(p: Vector3<f32>) -> Vector3<S> {
+    Vector3::new(S::from_f64(p.x as f64), S::from_f64(p.y as f64), S::from_f64(p.z as f64))
+}
+
+pub fn add<S: Scalar>(a: Vector3<S>, b: Vector3<S>) -> Vector3<S> {
+    Vector3::new(a.x + b.x, a.y + b.y, a.z + b.z)
+}
+
+pub fn sub<S: Scalar>(a: Vector3<S>, b: Vector3<S>) -> Vector3<S> {
+    Vector3::new(a.x - b.x, a.y - b.y, a.z - b.z)
+}
+
+pub fn scale<S: Scalar>(a: Vector3<S>, s: S) -> Vector3<S> {
+    Vector3::new(a.x * s, a.y * s, a.z * s)
+}
+
+pub fn dot<S: Scalar>(a: Vector3<S>, b: Vector3<S>) -> S {
+    a.x * b.x + a.y * b.y + a.z * b.z
+}
+
+pub fn cross<S: Scalar>(a: Vector3<S>, b: Vector3<S>) -> Vector3<S> {
+    Vector3::new(a.y * b.z - a.z * b.y, a.z * b.x - a.x * b.z, a.x * b.y - a.y * b.x)
+}
+
+pub fn magnitude<S: Scalar>(a: Vector3<S>) -> S {
+    dot(a, a).sqrt()
+}
+
+pub fn normalize<S: Scalar>(a: Vector3<S>) -> Vector3<S> {
+    scale(a, S::from_f64(1.0) / magnitude(a))
+}
@@ -0,0 +1,470 @@
+//! Planar cross-sections of an SDF surface.
+//!
+//! A slice samples the distance field on a plane and can be written as a false-colour
+//! PNG (with the zero contour marked) and as SVG polylines of the zero contour.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use cgmath::{InnerSpace, Vector3};
+use sdf_viewer::sdf::SDFSurface;
+
+/// Coordinate axis used to define axis-aligned slice planes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+impl Axis {
+    pub fn unit(self) -> Vector3<f32> {
+        match self {
+            Axis::X => Vector3::new(1.0, 0.0, 0.0),
+            Axis::Y => Vector3::new(0.0, 1.0, 0.0),
+            Axis::Z => Vector3::new(0.0, 0.0, 1.0),
+        }
+    }
+
+    pub fn component(self, p: Vector3<f32>) -> f32 {
+        match self {
+            Axis::X => p.x,
+            Axis::Y => p.y,
+            Axis::Z => p.z,
+        }
+    }
+}
+
+impl std::str::FromStr for Axis {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "x" | "X" => Ok(Axis::X),
+            "y" | "Y" => Ok(Axis::Y),
+            "z" | "Z" => Ok(Axis::Z),
+            _ => Err(format!("unknown axis `{}`, expected x, y or z", s)),
+        }
+    }
+}
+
+/// A plane with an in-plane orthonormal frame `(u, v)`.
+///
+/// Plane coordinates `(s, t)` map to the world point `origin + u * s + v * t`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SlicePlane {
+    pub origin: Vector3<f32>,
+    pub normal: Vector3<f32>,
+    pub u: Vector3<f32>,
+    pub v: Vector3<f32>,
+}
+
+impl SlicePlane {
+    /// Plane through `point` with the given `normal` (will be normalized).
+    pub fn new(point: Vector3<f32>, normal: Vector3<f32>) -> Self {
+        let normal = normal.normalize();
+        // Pick the world axis least aligned with the normal to build the frame
+        let helper = if normal.z.abs() < 0.9 {
+            Vector3::new(0.0, 0.0, 1.0)
+        } else {
+            Vector3::new(0.0, 1.0, 0.0)
+        };
+        let u = helper.cross(normal).normalize();
+        let v = normal.cross(u);
+        Self { origin: point, normal, u, v }
+    }
+
+    /// Plane perpendicular to `axis` at `offset`.
+    ///
+    /// The in-plane axes follow the usual drawing conventions:
+    /// x-slices show (y, z), y-slices show (x, z) and z-slices show (x, y).
+    pub fn axis(axis: Axis, offset: f32) -> Self {
+        let normal = axis.unit();
+        let (u, v) = match axis {
+            Axis::X => (Axis::Y.unit(), Axis::Z.unit()),
+            Axis::Y => (Axis::X.unit(), Axis::Z.unit()),
+            Axis::Z => (Axis::X.unit(), Axis::Y.unit()),
+        };
+        Self { origin: normal * offset, normal, u, v }
+    }
+
+    /// Parse a plane from command line arguments.
+    ///
+    /// Accepts either `<axis> <offset>` (e.g. `z 12.5`) or
+    /// `<px,py,pz> <nx,ny,nz>` (a point on the plane and its normal).
+    pub fn from_args(args: &[String]) -> Result<Self, String> {
+        match args {
+            [first, second] => {
+                if let Ok(axis) = first.parse::<Axis>() {
+                    let offset = second
+                        .parse::<f32>()
+                        .map_err(|e| format!("invalid offset `{}`: {}", second, e))?;
+                    Ok(Self::axis(axis, offset))
+                } else {
+                    Ok(Self::new(parse_vector(first)?, parse_vector(second)?))
+                }
+            }
+            _ => Err("expected `<axis> <offset>` or `<px,py,pz> <nx,ny,nz>`".to_string()),
+        }
+    }
+
+    /// World position of the plane coordinates `(s, t)`.
+    pub fn point(&self, s: f32, t: f32) -> Vector3<f32> {
+        self.origin + self.u * s + self.v * t
+    }
+
+    /// Plane coordinates of the projection of world point `p`.
+    pub fn project(&self, p: Vector3<f32>) -> [f32; 2] {
+        let d = p - self.origin;
+        [d.dot(self.u), d.dot(self.v)]
+    }
+}
+
+fn parse_vector(s: &str) -> Result<Vector3<f32>, String> {
+    let parts = s
+        .split(',')
+        .map(|c| c.trim().parse::<f32>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("invalid vector `{}`: {}", s, e))?;
+    match parts[..] {
+        [x, y, z] => Ok(Vector3::new(x, y, z)),
+        _ => Err(format!("invalid vector `{}`: expected three comma separated numbers", s)),
+    }
+}
+
+/// Distances sampled on a regular grid over a plane.
+///
+/// Sample `(i, j)` lies at plane coordinates `(min[0] + i * step, min[1] + j * step)`.
+#[derive(Debug, Clone)]
+pub struct Slice {
+    pub plane: SlicePlane,
+    pub min: [f32; 2],
+    pub step: f32,
+    pub width: usize,
+    pub height: usize,
+    pub distances: Vec<f32>,
+}
+
+impl Slice {
+    /// Sample `surface` on `plane` with a grid spacing of `step` model units.
+    ///
+    /// The sampled region is the projection of the surface's bounding box onto the plane.
+    pub fn sample<S: SDFSurface + ?Sized>(surface: &S, plane: SlicePlane, step: f32) -> Self {
+        let (min, max, _) = project_box(surface.bounding_box(), plane);
+        Self::sample_region(surface, plane, min, max, step)
+    }
+
+    /// Sample `surface` on the rectangle `[min, max]` of plane coordinates.
+    pub fn sample_region<S: SDFSurface + ?Sized>(
+        surface: &S,
+        plane: SlicePlane,
+        min: [f32; 2],
+        max: [f32; 2],
+        step: f32,
+    ) -> Self {
+        let width = ((max[0] - min[0]) / step).ceil() as usize + 1;
+        let height = ((max[1] - min[1]) / step).ceil() as usize + 1;
+        let mut distances = Vec::with_capacity(width * height);
+        for j in 0..height {
+            for i in 0..width {
+                let p = plane.point(min[0] + i as f32 * step, min[1] + j as f32 * step);
+                distances.push(surface.sample(p, true).distance);
+            }
+        }
+        Self { plane, min, step, width, height, distances }
+    }
+
+    /// Outline of `surface` seen along the plane normal, over the bounding box projection.
+    ///
+    /// Each sample holds the smallest distance on the line through it along the normal,
+    /// sampled every `step` across the bounding box, so the zero contour is the silhouette.
+    pub fn silhouette<S: SDFSurface + ?Sized>(surface: &S, plane: SlicePlane, step: f32) -> Self {
+        let (min, max, [near, far]) = project_box(surface.bounding_box(), plane);
+        let width = ((max[0] - min[0]) / step).ceil() as usize + 1;
+        let height = ((max[1] - min[1]) / step).ceil() as usize + 1;
+        let layers = ((far - near) / step).ceil() as usize + 1;
+        let mut distances = Vec::with_capacity(width * height);
+        for j in 0..height {
+            for i in 0..width {
+                let p = plane.point(min[0] + i as f32 * step, min[1] + j as f32 * step);
+                distances.push(
+                    (0..layers)
+                        .map(|k| surface.sample(p + plane.normal * (near + k as f32 * step), true).distance)
+                        .fold(f32::INFINITY, f32::min),
+                );
+            }
+        }
+        Self { plane, min, step, width, height, distances }
+    }
+
+    pub fn distance(&self, i: usize, j: usize) -> f32 {
+        self.distances[j * self.width + i]
+    }
+
+    /// Plane coordinates of grid sample `(i, j)`.
+    pub fn coords(&self, i: usize, j: usize) -> [f32; 2] {
+        [self.min[0] + i as f32 * self.step, self.min[1] + j as f32 * self.step]
+    }
+
+    /// Zero contour as polylines in plane coordinates (marching squares).
+    ///
+    /// Closed loops repeat their first point at the end.
+    pub fn contours(&self) -> Vec<Vec<[f32; 2]>> {
+        if self.width < 2 || self.height < 2 {
+            return vec![];
+        }
+
+        // Edge ids: 2 * sample index for the edge towards +i, 2 * sample index + 1 towards +j
+        let h_edge = |i: usize, j: usize| 2 * (j * self.width + i);
+        let v_edge = |i: usize, j: usize| 2 * (j * self.width + i) + 1;
+
+        let mut segments: Vec<[usize; 2]> = vec![];
+        for j in 0..self.height - 1 {
+            for i in 0..self.width - 1 {
+                let a = self.distance(i, j);
+                let b = self.distance(i + 1, j);
+                let c = self.distance(i + 1, j + 1);
+                let d = self.distance(i, j + 1);
+                let case = (a < 0.0) as u8
+                    | ((b < 0.0) as u8) << 1
+                    | ((c < 0.0) as u8) << 2
+                    | ((d < 0.0) as u8) << 3;
+
+                let bottom = h_edge(i, j);
+                let right = v_edge(i + 1, j);
+                let top = h_edge(i, j + 1);
+                let left = v_edge(i, j);
+                let center_inside = a + b + c + d < 0.0;
+
+                match case {
+                    1 | 14 => segments.push([left, bottom]),
+                    2 | 13 => segments.push([bottom, right]),
+                    3 | 12 => segments.push([left, right]),
+                    4 | 11 => segments.push([right, top]),
+                    6 | 9 => segments.push([bottom, top]),
+                    7 | 8 => segments.push([left, top]),
+                    5 | 10 => {
+                        // Saddle: decide by the cell center which diagonal is connected
+                        if (case == 5) == center_inside {
+                            segments.push([bottom, right]);
+                            segments.push([top, left]);
+                        } else {
+                            segments.push([left, bottom]);
+                            segments.push([right, top]);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let mut by_edge: HashMap<usize, Vec<usize>> = HashMap::new();
+        for (s, seg) in segments.iter().enumerate() {
+            for &e in seg {
+                by_edge.entry(e).or_default().push(s);
+            }
+        }
+
+        let mut used = vec![false; segments.len()];
+        let mut polylines = vec![];
+        for start in 0..segments.len() {
+            if used[start] {
+                continue;
+            }
+            used[start] = true;
+            let mut edges = vec![segments[start][0], segments[start][1]];
+
+            // Extend forwards from the last edge, then backwards from the first one
+            for backwards in [false, true] {
+                loop {
+                    let end = if backwards { edges[0] } else { *edges.last().unwrap() };
+                    let next = by_edge[&end].iter().copied().find(|&s| !used[s]);
+                    let Some(next) = next else { break };
+                    used[next] = true;
+                    let [e0, e1] = segments[next];
+                    let other = if e0 == end { e1 } else { e0 };
+                    if backwards {
+                        edges.insert(0, other);
+                    } else {
+                        edges.push(other);
+                    }
+                }
+            }
+
+            polylines.push(edges.into_iter().map(|e| self.edge_point(e)).collect());
+        }
+        polylines
+    }
+
+    /// Interpolated zero crossing on a grid edge.
+    fn edge_point(&self, edge: usize) -> [f32; 2] {
+        let sample = edge / 2;
+        let (i, j) = (sample % self.width, sample / self.width);
+        let (i2, j2) = if edge & 1 == 0 { (i + 1, j) } else { (i, j + 1) };
+        let d0 = self.distance(i, j);
+        let d1 = self.distance(i2, j2);
+        let t = if d0 == d1 { 0.5 } else { d0 / (d0 - d1) };
+        let [s0, t0] = self.coords(i, j);
+        let [s1, t1] = self.coords(i2, j2);
+        [s0 + (s1 - s0) * t, t0 + (t1 - t0) * t]
+    }
+
+    /// False-colour RGB image, one pixel per sample, first row at the top (maximum v).
+    ///
+    /// Outside is orange, inside is blue, both banded by distance; the zero contour is white.
+    pub fn false_colour(&self) -> Vec<u8> {
+        // Inside and outside are normalized separately, thin parts would be invisible otherwise
+        let max_out = self.distances.iter().fold(1e-6f32, |m, &d| m.max(d));
+        let max_in = self.distances.iter().fold(1e-6f32, |m, &d| m.max(-d));
+        let mut rgb = Vec::with_capacity(self.width * self.height * 3);
+        for j in (0..self.height).rev() {
+            for i in 0..self.width {
+                let d = self.distance(i, j);
+                let colour = if self.on_contour(i, j) {
+                    [1.0, 1.0, 1.0]
+                } else {
+                    let (base, max_abs) =
+                        if d < 0.0 { ([0.65, 0.85, 1.0], max_in) } else { ([0.9, 0.6, 0.3], max_out) };
+                    let fade = 0.4 + 0.6 * (d.abs() / max_abs).sqrt();
+                    let stripes = 0.85 + 0.15 * (16.0 * std::f32::consts::PI * d / max_abs).cos();
+                    base.map(|c| c * fade * stripes)
+                };
+                rgb.extend(colour.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8));
+            }
+        }
+        rgb
+    }
+
+    /// Whether a 4-neighbour of sample `(i, j)` lies on the other side of the zero contour.
+    fn on_contour(&self, i: usize, j: usize) -> bool {
+        let inside = self.distance(i, j) < 0.0;
+        [(1, 0), (-1, 0), (0, 1), (0, -1)].iter().any(|&(di, dj)| {
+            let (ni, nj) = (i as isize + di, j as isize + dj);
+            ni >= 0
+                && nj >= 0
+                && (ni as usize) < self.width
+                && (nj as usize) < self.height
+                && (self.distance(ni as usize, nj as usize) < 0.0) != inside
+        })
+    }
+
+    /// RGB image with the colours of `surface` inside the zero contour and the dimmed false
+    /// colour outside.
+    ///
+    /// The surface is sampled again (with colours) at every inside pixel.
+    pub fn surface_colour<S: SDFSurface + ?Sized>(&self, surface: &S) -> Vec<u8> {
+        let mut rgb = self.false_colour();
+        for (row, j) in (0..self.height).rev().enumerate() {
+            for i in 0..self.width {
+                let pixel = &mut rgb[(row * self.width + i) * 3..][..3];
+                if self.on_contour(i, j) {
+                    continue;
+                }
+                if self.distance(i, j) < 0.0 {
+                    let [s, t] = self.coords(i, j);
+                    let c = surface.sample(self.plane.point(s, t), false).color;
+                    pixel.copy_from_slice(&[c.x, c.y, c.z].map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8));
+                } else {
+                    pixel.iter_mut().for_each(|c| *c /= 2);
+                }
+            }
+        }
+        rgb
+    }
+
+    /// Write the false-colour distance image as a PNG.
+    pub fn write_png(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        write_rgb_png(path, self.width, self.height, &self.false_colour())
+    }
+
+    /// Write the [`Slice::surface_colour`] image of `surface` as a PNG.
+    pub fn write_surface_png<S: SDFSurface + ?Sized>(&self, path: impl AsRef<Path>, surface: &S) -> io::Result<()> {
+        write_rgb_png(path, self.width, self.height, &self.surface_colour(surface))
+    }
+
+    /// Write the zero contour as SVG polylines.
+    ///
+    /// One model unit is one millimetre; the v axis points up as in the PNG.
+    pub fn write_svg(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+        let w = (self.width - 1) as f32 * self.step;
+        let h = (self.height - 1) as f32 * self.step;
+        let top = self.min[1] + h;
+        writeln!(
+            out,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}mm" height="{h}mm" viewBox="{} {} {w} {h}">"#,
+            self.min[0], -top,
+        )?;
+        for polyline in self.contours() {
+            let points = polyline
+                .iter()
+                .map(|[s, t]| format!("{},{}", s, -t))
+                .collect::<Vec<_>>()
+                .join(" ");
+            writeln!(
+                out,
+                r#"  <polyline points="{}" fill="none" stroke="black" stroke-width="{}"/>"#,
+                points,
+                self.step * 0.5,
+            )?;
+        }
+        writeln!(out, "</svg>")
+    }
+
+    /// Write `<prefix>.png` and `<prefix>.svg`.
+    pub fn write(&self, prefix: &str) -> io::Result<()> {
+        self.write_png(format!("{}.png", prefix))?;
+        self.write_svg(format!("{}.svg", prefix))
+    }
+}
+
+/// Write the [`Slice::surface_colour`] images of `frames` side by side as one PNG, e.g. the
+/// steps of a motion, separated by white columns. The frames are aligned at the top.
+pub fn write_surface_strip_png<S: SDFSurface + ?Sized>(path: impl AsRef<Path>, frames: &[(Slice, &S)]) -> io::Result<()> {
+    const GAP: usize = 4;
+    let height = frames.iter().map(|(slice, _)| slice.height).max().unwrap_or(0);
+    let width = frames.iter().map(|(slice, _)| slice.width + GAP).sum::<usize>().saturating_sub(GAP);
+    let mut rgb = vec![255; width * height * 3];
+    let mut left = 0;
+    for (slice, surface) in frames {
+        let image = slice.surface_colour(*surface);
+        for row in 0..slice.height {
+            let src = &image[row * slice.width * 3..][..slice.width * 3];
+            rgb[(row * width + left) * 3..][..slice.width * 3].copy_from_slice(src);
+        }
+        left += slice.width + GAP;
+    }
+    write_rgb_png(path, width, height, &rgb)
+}
+
+/// Plane coordinate ranges `(min, max)` and the depth range along the normal of a box.
+fn project_box([lo, hi]: [Vector3<f32>; 2], plane: SlicePlane) -> ([f32; 2], [f32; 2], [f32; 2]) {
+    let mut min = [f32::INFINITY; 2];
+    let mut max = [f32::NEG_INFINITY; 2];
+    let mut depth = [f32::INFINITY, f32::NEG_INFINITY];
+    for corner in 0..8 {
+        let c = Vector3::new(
+            if corner & 1 == 0 { lo.x } else { hi.x },
+            if corner & 2 == 0 { lo.y } else { hi.y },
+            if corner & 4 == 0 { lo.z } else { hi.z },
+        );
+        let [s, t] = plane.project(c);
+        let d = (c - plane.origin).dot(plane.normal);
+        min = [min[0].min(s), min[1].min(t)];
+        max = [max[0].max(s), max[1].max(t)];
+        depth = [depth[0].min(d), depth[1].max(d)];
+    }
+    (min, max, depth)
+}
+
+fn write_rgb_png(path: impl AsRef<Path>, width: usize, height: usize, rgb: &[u8]) -> io::Result<()> {
+    let file = BufWriter::new(File::create(path)?);
+    let mut encoder = png::Encoder::new(file, width as u32, height as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(io::Error::other)?;
+    writer.write_image_data(rgb).map_err(io::Error::other)?;
+    writer.finish().map_err(io::Error::other)
+}
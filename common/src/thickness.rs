@@ -0,0 +1,234 @@
+//! Local wall thickness and thin-feature detection.
+//!
+//! Each vertex of the surface mesh is snapped onto the surface and a ray is sphere-traced from
+//! it along the inward normal until it leaves the solid. The length of the ray estimates the
+//! wall thickness and its middle the medial axis. Unlike inscribed balls, which shrink to
+//! nothing at every sharp convex edge, the ray crosses an edge along its bisector. Thin
+//! vertices joined by mesh edges form the reported regions, and so do vertices that could not
+//! be measured.
+
+use std::fmt;
+use std::path::Path;
+
+use cgmath::{InnerSpace, Vector3};
+use sdf_viewer::sdf::SDFSurface;
+
+use crate::mesh::{Mesh, MeshConfig};
+use crate::print::gradient;
+
+/// Parameters of [`thickness`].
+#[derive(Debug, Clone)]
+pub struct ThicknessConfig {
+    /// Walls thinner than this are reported, in the units of the surface.
+    pub min_thickness: f32,
+    /// Smallest ray step relative to the mesh cell size, the accuracy of the thickness.
+    pub accuracy: f32,
+    pub mesh: MeshConfig,
+}
+
+impl Default for ThicknessConfig {
+    fn default() -> Self {
+        Self {
+            min_thickness: 1.0,
+            accuracy: 0.05,
+            mesh: MeshConfig { resolution: 96 },
+        }
+    }
+}
+
+/// Connected surface area thinner than the minimum, or without a thickness.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThinRegion {
+    /// The thinnest vertex of the region, the first one if unmeasured.
+    pub point: Vector3<f32>,
+    /// NaN for unmeasured regions.
+    pub thickness: f32,
+    pub vertices: usize,
+    /// Bounding box of the region's vertices.
+    pub min: Vector3<f32>,
+    pub max: Vector3<f32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ThicknessReport {
+    pub mesh: Mesh,
+    /// Estimated wall thickness at each mesh vertex, NaN where the vertex could not be moved onto
+    /// the surface. Such vertices are artefacts of features finer than the mesh cells.
+    pub thickness: Vec<f32>,
+    /// Thin regions, thinnest first.
+    pub regions: Vec<ThinRegion>,
+    /// Regions of unmeasured vertices, largest first. They can hide the thinnest walls.
+    pub unmeasured_regions: Vec<ThinRegion>,
+    pub min_thickness: f32,
+}
+
+impl ThicknessReport {
+    pub fn is_ok(&self) -> bool {
+        self.regions.is_empty() && self.unmeasured_regions.is_empty()
+    }
+
+    /// Smallest thickness anywhere on the surface.
+    pub fn thinnest(&self) -> Option<f32> {
+        self.thickness.iter().copied().filter(|t| !t.is_nan()).reduce(f32::min)
+    }
+
+    /// Number of vertices without a thickness.
+    pub fn unmeasured(&self) -> usize {
+        self.thickness.iter().filter(|t| t.is_nan()).count()
+    }
+
+    /// Red below the minimum thickness, yellow at it turning to green at twice of it, grey where
+    /// unmeasured.
+    pub fn colour(&self, thickness: f32) -> Vector3<f32> {
+        if thickness.is_nan() {
+            return Vector3::new(0.5, 0.5, 0.5);
+        }
+        if thickness < self.min_thickness {
+            return Vector3::new(0.9, 0.1, 0.1);
+        }
+        let s = ((thickness - self.min_thickness) / self.min_thickness).clamp(0.0, 1.0);
+        Vector3::new(1.0 - 0.8 * s, 0.9, 0.1)
+    }
+
+    /// The mesh with each vertex coloured by [`ThicknessReport::colour`].
+    pub fn write_ply(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let colours: Vec<Vector3<f32>> = self.thickness.iter().map(|&t| self.colour(t)).collect();
+        self.mesh.write_ply_colours(path, &colours)
+    }
+}
+
+impl fmt::Display for ThicknessReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{} vertices ({} unmeasured in {} regions), thinnest {:.3}, {} regions thinner than {}",
+            self.thickness.len(),
+            self.unmeasured(),
+            self.unmeasured_regions.len(),
+            self.thinnest().unwrap_or(f32::INFINITY),
+            self.regions.len(),
+            self.min_thickness
+        )?;
+        for r in self.regions.iter().chain(&self.unmeasured_regions) {
+            writeln!(
+                f,
+                "  {:.3} at ({:.3}, {:.3}, {:.3}), {} vertices in ({:.3}, {:.3}, {:.3})..({:.3}, {:.3}, {:.3})",
+                r.thickness, r.point.x, r.point.y, r.point.z, r.vertices, r.min.x, r.min.y, r.min.z, r.max.x, r.max.y, r.max.z
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Length of the ray from the surface point `p` along `inward` inside the solid, at most
+/// `max_length`. The ray may start up to `max_entry` outside, NaN if it does not enter by then,
+/// and steps by the distance to the surface but at least `min_step`.
+pub fn ray_thickness<S: SDFSurface + ?Sized>(
+    surface: &S,
+    p: Vector3<f32>,
+    inward: Vector3<f32>,
+    min_step: f32,
+    max_entry: f32,
+    max_length: f32,
+) -> f32 {
+    let distance = |t: f32| surface.sample(p + inward * t, true).distance;
+    let mut entry = 0.0;
+    while distance(entry) >= 0.0 {
+        entry += min_step;
+        if entry > max_entry {
+            return f32::NAN;
+        }
+    }
+    let mut t = entry;
+    while t - entry < max_length {
+        let d = distance(t);
+        if d >= 0.0 {
+            return t - entry;
+        }
+        t += (-d).max(min_step);
+    }
+    max_length
+}
+
+/// Moves `p` onto the surface along the gradient until the distance is below `tolerance`.
+/// Returns the point and the outward normal there, or `None` if the gradient vanishes or the
+/// point does not converge, as in the valley of the field between two nearly touching features.
+fn project<S: SDFSurface + ?Sized>(surface: &S, mut p: Vector3<f32>, h: f32, tolerance: f32) -> Option<(Vector3<f32>, Vector3<f32>)> {
+    for _ in 0..16 {
+        let normal = gradient(surface, p, h)?;
+        let d = surface.sample(p, true).distance;
+        if d.abs() < tolerance {
+            return Some((p, normal));
+        }
+        p -= normal * d;
+    }
+    None
+}
+
+/// Wall thickness at the vertices of the mesh of `surface` and the regions below
+/// `min_thickness`.
+pub fn thickness<S: SDFSurface + ?Sized>(surface: &S, cfg: &ThicknessConfig) -> ThicknessReport {
+    let mesh = Mesh::extract(surface, &cfg.mesh);
+    let [lo, hi] = surface.bounding_box();
+    let size = hi - lo;
+    let cell = size.x.max(size.y).max(size.z) / cfg.mesh.resolution as f32;
+    let thickness: Vec<f32> = mesh
+        .positions
+        .iter()
+        .map(|&p| match project(surface, p, cell / 8.0, cell * cfg.accuracy) {
+            Some((on_surface, normal)) => {
+                ray_thickness(surface, on_surface, -normal, cell * cfg.accuracy, cell, size.magnitude())
+            }
+            None => f32::NAN,
+        })
+        .collect();
+
+    let mut regions = regions_of(&mesh, &thickness, |t| t < cfg.min_thickness);
+    regions.sort_by(|a, b| a.thickness.total_cmp(&b.thickness));
+    let mut unmeasured_regions = regions_of(&mesh, &thickness, f32::is_nan);
+    unmeasured_regions.sort_by_key(|r| std::cmp::Reverse(r.vertices));
+    ThicknessReport { mesh, thickness, regions, unmeasured_regions, min_thickness: cfg.min_thickness }
+}
+
+/// Regions of the vertices whose thickness passes `selected`, joined by mesh edges.
+fn regions_of(mesh: &Mesh, thickness: &[f32], selected: impl Fn(f32) -> bool) -> Vec<ThinRegion> {
+    // Union-find over the mesh edges between selected vertices
+    let thin: Vec<bool> = thickness.iter().map(|&t| selected(t)).collect();
+    let mut parent: Vec<usize> = (0..thickness.len()).collect();
+    fn root(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+    for t in &mesh.triangles {
+        for (a, b) in [(t[0], t[1]), (t[1], t[2]), (t[2], t[0])] {
+            let (a, b) = (a as usize, b as usize);
+            if thin[a] && thin[b] {
+                let (ra, rb) = (root(&mut parent, a), root(&mut parent, b));
+                parent[ra] = rb;
+            }
+        }
+    }
+
+    let mut regions: Vec<ThinRegion> = vec![];
+    let mut region_of = vec![usize::MAX; thickness.len()];
+    for i in (0..thickness.len()).filter(|&i| thin[i]) {
+        let r = root(&mut parent, i);
+        let p = mesh.positions[i];
+        if region_of[r] == usize::MAX {
+            region_of[r] = regions.len();
+            regions.push(ThinRegion { point: p, thickness: thickness[i], vertices: 0, min: p, max: p });
+        }
+        let region = &mut regions[region_of[r]];
+        region.vertices += 1;
+        region.min = Vector3::new(region.min.x.min(p.x), region.min.y.min(p.y), region.min.z.min(p.z));
+        region.max = Vector3::new(region.max.x.max(p.x), region.max.y.max(p.y), region.max.z.max(p.z));
+        if thickness[i] < region.thickness {
+            region.thickness = thickness[i];
+            region.point = p;
+        }
+    }
+    regions
+}
@@ -0,0 +1,284 @@
+//! Distance-property checks for SDF objects.
+//!
+//! Sphere tracing relies on the sampled value never overestimating the distance to the surface.
+//! Non-rigid transforms (scaling, bends) and some joins break that and show up as holes in the
+//! viewer. These checks estimate how badly an object violates the property and where.
+
+use std::fmt;
+
+use cgmath::{InnerSpace, Vector3};
+use sdf_viewer::sdf::SDFSurface;
+
+/// Number of worst regions per check listed by the report's `Display`.
+const REPORT_REGIONS: usize = 10;
+
+/// Parameters of [`validate`].
+#[derive(Debug, Clone)]
+pub struct ValidationConfig {
+    /// Number of random sample points for the Lipschitz and sign checks.
+    pub samples: usize,
+    /// Seed of the deterministic random generator.
+    pub seed: u64,
+    /// Distance between paired Lipschitz samples, relative to the bounding box diagonal.
+    pub pair_distance: f32,
+    /// Allowed excess of the Lipschitz constant over 1.
+    pub lipschitz_tolerance: f32,
+    /// Samples per side of each bounding box face.
+    pub face_resolution: usize,
+    /// Cells per side of the grid used to group violations into regions.
+    pub region_resolution: usize,
+}
+
+impl Default for ValidationConfig {
+    fn default() -> Self {
+        Self {
+            samples: 20000,
+            seed: 1,
+            pair_distance: 1e-3,
+            lipschitz_tolerance: 0.02,
+            face_resolution: 32,
+            region_resolution: 8,
+        }
+    }
+}
+
+/// A sample that failed one of the checks.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Violation {
+    pub point: Vector3<f32>,
+    /// Check specific severity: the gradient estimate, the negative distance on the
+    /// bounding box, or the distance of the probe with the wrong sign.
+    pub value: f32,
+}
+
+/// Violations grouped into a cell of the region grid.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Region {
+    pub min: Vector3<f32>,
+    pub max: Vector3<f32>,
+    pub count: usize,
+    pub worst: Violation,
+}
+
+#[derive(Debug, Clone)]
+pub struct ValidationReport {
+    pub bounding_box: [Vector3<f32>; 2],
+    pub region_resolution: usize,
+    /// Largest gradient magnitude estimate seen.
+    pub lipschitz: f32,
+    /// Pairs whose gradient estimate exceeds `1 + lipschitz_tolerance`.
+    pub lipschitz_violations: Vec<Violation>,
+    /// Negative samples on the bounding box faces.
+    pub bounding_box_violations: Vec<Violation>,
+    /// Probes within the distance ball of a sample that have the opposite sign.
+    pub sign_violations: Vec<Violation>,
+}
+
+impl ValidationReport {
+    pub fn is_ok(&self) -> bool {
+        self.lipschitz_violations.is_empty()
+            && self.bounding_box_violations.is_empty()
+            && self.sign_violations.is_empty()
+    }
+
+    /// Group violations into cells of the region grid, worst first.
+    pub fn regions(&self, violations: &[Violation]) -> Vec<Region> {
+        let [lo, hi] = self.bounding_box;
+        let n = self.region_resolution.max(1);
+        let cell = (hi - lo) / n as f32;
+        let index = |x: f32, lo: f32, size: f32| {
+            (((x - lo) / size).floor().max(0.0) as usize).min(n - 1)
+        };
+
+        let mut regions: Vec<(usize, Region)> = vec![];
+        for v in violations {
+            let (i, j, k) = (
+                index(v.point.x, lo.x, cell.x),
+                index(v.point.y, lo.y, cell.y),
+                index(v.point.z, lo.z, cell.z),
+            );
+            let id = (k * n + j) * n + i;
+            match regions.iter_mut().find(|(rid, _)| *rid == id) {
+                Some((_, region)) => {
+                    region.count += 1;
+                    if v.value.abs() > region.worst.value.abs() {
+                        region.worst = *v;
+                    }
+                }
+                None => {
+                    let min = lo + Vector3::new(
+                        i as f32 * cell.x,
+                        j as f32 * cell.y,
+                        k as f32 * cell.z,
+                    );
+                    regions.push((id, Region { min, max: min + cell, count: 1, worst: *v }));
+                }
+            }
+        }
+
+        let mut regions = regions.into_iter().map(|(_, r)| r).collect::<Vec<_>>();
+        regions.sort_by(|a, b| b.worst.value.abs().total_cmp(&a.worst.value.abs()));
+        regions
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "estimated Lipschitz constant: {}", self.lipschitz)?;
+        for (name, violations) in [
+            ("Lipschitz", &self.lipschitz_violations),
+            ("bounding box", &self.bounding_box_violations),
+            ("sign", &self.sign_violations),
+        ] {
+            let regions = self.regions(violations);
+            writeln!(f, "{} violations: {} in {} regions", name, violations.len(), regions.len())?;
+            for r in regions.iter().take(REPORT_REGIONS) {
+                let p = r.worst.point;
+                writeln!(
+                    f,
+                    "  region ({:.3}, {:.3}, {:.3})..({:.3}, {:.3}, {:.3}): {} samples, worst {} at ({:.3}, {:.3}, {:.3})",
+                    r.min.x, r.min.y, r.min.z, r.max.x, r.max.y, r.max.z,
+                    r.count, r.worst.value, p.x, p.y, p.z,
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Small deterministic generator (SplitMix64), so that reports are reproducible.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `[0, 1)`.
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Uniform point in the box `[lo, hi]`.
+    pub fn point_in_box(&mut self, lo: Vector3<f32>, hi: Vector3<f32>) -> Vector3<f32> {
+        Vector3::new(
+            lo.x + (hi.x - lo.x) * self.next_f32(),
+            lo.y + (hi.y - lo.y) * self.next_f32(),
+            lo.z + (hi.z - lo.z) * self.next_f32(),
+        )
+    }
+
+    /// Uniform point in the unit ball.
+    pub fn point_in_unit_ball(&mut self) -> Vector3<f32> {
+        let one = Vector3::new(1.0, 1.0, 1.0);
+        loop {
+            let p = self.point_in_box(-one, one);
+            if p.magnitude2() <= 1.0 {
+                return p;
+            }
+        }
+    }
+
+    /// Uniform unit vector.
+    pub fn direction(&mut self) -> Vector3<f32> {
+        loop {
+            let p = self.point_in_unit_ball();
+            if p.magnitude2() > 1e-4 {
+                return p.normalize();
+            }
+        }
+    }
+}
+
+/// Run all distance-property checks on `surface`.
+pub fn validate<S: SDFSurface + ?Sized>(surface: &S, cfg: &ValidationConfig) -> ValidationReport {
+    let bounding_box = surface.bounding_box();
+    let (lipschitz, lipschitz_violations) = check_lipschitz(surface, cfg);
+    ValidationReport {
+        bounding_box,
+        region_resolution: cfg.region_resolution,
+        lipschitz,
+        lipschitz_violations,
+        bounding_box_violations: check_bounding_box(surface, cfg.face_resolution),
+        sign_violations: check_sign(surface, cfg),
+    }
+}
+
+/// Estimate the Lipschitz constant from close random pairs.
+///
+/// Returns the largest estimate and the pairs (at their midpoint) exceeding the tolerance.
+pub fn check_lipschitz<S: SDFSurface + ?Sized>(
+    surface: &S,
+    cfg: &ValidationConfig,
+) -> (f32, Vec<Violation>) {
+    let [lo, hi] = surface.bounding_box();
+    let h = (hi - lo).magnitude() * cfg.pair_distance;
+    let mut rng = Rng::new(cfg.seed);
+    let mut lipschitz = 0.0f32;
+    let mut violations = vec![];
+    for _ in 0..cfg.samples {
+        let p = rng.point_in_box(lo, hi);
+        let q = p + rng.direction() * h;
+        let gradient = (surface.sample(p, true).distance - surface.sample(q, true).distance).abs()
+            / (q - p).magnitude();
+        lipschitz = lipschitz.max(gradient);
+        if gradient > 1.0 + cfg.lipschitz_tolerance {
+            violations.push(Violation { point: (p + q) / 2.0, value: gradient });
+        }
+    }
+    (lipschitz, violations)
+}
+
+/// Sample the six bounding box faces on a `resolution`^2 grid and return negative samples.
+pub fn check_bounding_box<S: SDFSurface + ?Sized>(surface: &S, resolution: usize) -> Vec<Violation> {
+    let [lo, hi] = surface.bounding_box();
+    let size = hi - lo;
+    let steps = (0..=resolution).map(|i| i as f32 / resolution as f32).collect::<Vec<_>>();
+    let mut violations = vec![];
+    for axis in 0..3 {
+        for side in [0.0, 1.0] {
+            for &s in &steps {
+                for &t in &steps {
+                    let uvw = match axis {
+                        0 => [side, s, t],
+                        1 => [s, side, t],
+                        _ => [s, t, side],
+                    };
+                    let p = lo + Vector3::new(size.x * uvw[0], size.y * uvw[1], size.z * uvw[2]);
+                    let d = surface.sample(p, true).distance;
+                    if d < 0.0 {
+                        violations.push(Violation { point: p, value: d });
+                    }
+                }
+            }
+        }
+    }
+    violations
+}
+
+/// Check that no point within the distance ball of a sample has the opposite sign.
+pub fn check_sign<S: SDFSurface + ?Sized>(surface: &S, cfg: &ValidationConfig) -> Vec<Violation> {
+    let [lo, hi] = surface.bounding_box();
+    let mut rng = Rng::new(cfg.seed.wrapping_add(1));
+    let mut violations = vec![];
+    for _ in 0..cfg.samples {
+        let p = rng.point_in_box(lo, hi);
+        let d = surface.sample(p, true).distance;
+        // Stay clear of the ball boundary, where the surface may legitimately touch
+        let q = p + rng.point_in_unit_ball() * d.abs() * (1.0 - cfg.lipschitz_tolerance);
+        let dq = surface.sample(q, true).distance;
+        if (d < 0.0) != (dq < 0.0) && dq != 0.0 {
+            violations.push(Violation { point: q, value: dq });
+        }
+    }
+    violations
+}
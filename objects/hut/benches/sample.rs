@@ -0,0 +1,15 @@
+// Sampling cost with and without distance_only and in batches, run with cargo bench -p press-hut
+
+use press_common::bench::{compare_batch, compare_distance_only};
+use press_common::golden::Field;
+use press_hut::{Material, MyObject};
+
+const RESOLUTION: usize = 24;
+
+fn main() {
+    let hut = MyObject::default();
+    let points = Field::grid(&hut, RESOLUTION).points;
+    compare_distance_only("material cascade", &hut, &points);
+    compare_distance_only("wood", &hut.with_material(Material::Wood), &points);
+    compare_batch("material cascade", &hut, &points);
+}
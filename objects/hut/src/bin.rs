@@ -1,11 +1,140 @@
 // Template binary for debugging your press object
 // Replace `press_template` with your actual crate name (e.g., `press_sphere`)
 
-use press_hut::MyObject;
+use press_common::bounds::{check_bounds, BoundsConfig};
+use press_common::mass::combine;
+use press_common::mesh::write_gltf;
+use press_common::slice::{Axis, Slice, SlicePlane};
+use press_common::thickness::{thickness, ThicknessConfig};
+use press_hut::cutlist::CutConfig;
+use press_hut::drawing::DrawingConfig;
+use press_hut::layout::LayoutConfig;
+use press_hut::overlap::OverlapConfig;
+use press_hut::{Config, MassConfig, Material, MeshConfig, MyObject};
 
 fn main() {
     let obj = MyObject::default();
-    println!("brick_count {}", obj.brick_rows.iter().map(|row| row.count * 2 + row.odd as usize).sum::<usize>());
+
+    match std::env::args().nth(1).as_deref() {
+        Some("bounds") => {
+            // The material cascade in `all_sdf_and_material` jumps between fields, so the
+            // default Lipschitz assumption would prune cells that still contain the roof
+            let cfg = BoundsConfig { lipschitz: 10.0, ..Default::default() };
+            println!("{}", check_bounds(&obj, &cfg));
+            return;
+        }
+        Some("mass") => {
+            let parts = obj.material_properties(&coarse_mass_config());
+            for (material, props) in &parts {
+                println!("{:?}\n{}", material, props);
+            }
+            let total = combine(&parts.iter().map(|(_, p)| *p).collect::<Vec<_>>());
+            println!("Total\n{}", total);
+            return;
+        }
+        Some("bom") => {
+            let bom = obj.bom(&coarse_mass_config());
+            match std::env::args().nth(2).as_deref() {
+                Some("json") => print!("{}", bom.to_json()),
+                _ => print!("{}", bom.to_csv()),
+            }
+            return;
+        }
+        Some("cutlist") => {
+            let cut_list = obj.cut_list(&CutConfig::default());
+            print!("{}", cut_list.to_csv());
+            eprintln!(
+                "{} members, {} joints, {} stock lengths of {} m, {:.3} m offcuts, {} oversize",
+                cut_list.cuts.len(),
+                cut_list.joints.len(),
+                cut_list.stock.len(),
+                cut_list.stock_length,
+                cut_list.waste(),
+                cut_list.oversize.len(),
+            );
+            return;
+        }
+        Some("layout") => {
+            let mut cfg = Config::default();
+            let report = cfg.solve_layout(&LayoutConfig::default());
+            for (i, row) in cfg.brick_rows.iter().enumerate() {
+                println!(
+                    "row {}: angle {:.3} step {:.3} count {} odd {} width factor {:.2}",
+                    i, row.angle, row.step, row.count, row.odd, row.brick_width_factor
+                );
+            }
+            for (i, brick) in cfg.door_bricks.iter().enumerate() {
+                println!(
+                    "door {}: angle {:.3} shift {:.3} width {:.3} frame height {:.3}",
+                    i, brick.angle, brick.shift, brick.brick_width, brick.frame_height
+                );
+            }
+            print!("{}", report);
+            println!("brick_count {}", MyObject::new(cfg).straw_bricks().len());
+            return;
+        }
+        Some("overlap") => {
+            // `overlap layout` checks the solved layout instead of the hand-tuned one
+            let obj = match std::env::args().nth(2).as_deref() {
+                Some("layout") => {
+                    let mut cfg = Config::default();
+                    cfg.solve_layout(&LayoutConfig::default());
+                    MyObject::new(cfg)
+                }
+                _ => obj,
+            };
+            let report = obj.overlaps(&OverlapConfig::default());
+            print!("{}", report);
+            let preview = obj.overlap_preview(&report);
+            std::fs::create_dir_all("out").unwrap();
+            for (axis, offset, name) in [(Axis::Z, 1.0, "z"), (Axis::Y, 0.0, "y")] {
+                let path = format!("out/hut_overlap_{}.png", name);
+                Slice::sample(&preview, SlicePlane::axis(axis, offset), 0.01)
+                    .write_surface_png(&path, &preview)
+                    .unwrap();
+                println!("wrote {}", path);
+            }
+            return;
+        }
+        Some("thickness") => {
+            // Only the frame has a minimum section, bricks trimmed to the door and roof are
+            // expected to end in slivers. Members are 0.1 m across, flag below half of that
+            let min_thickness = std::env::args().nth(2).map_or(0.05, |t| t.parse().expect("invalid thickness"));
+            let cfg = ThicknessConfig { min_thickness, ..Default::default() };
+            let report = thickness(&obj.with_material(Material::Wood), &cfg);
+            print!("{}", report);
+            std::fs::create_dir_all("out").unwrap();
+            report.write_ply("out/hut_wood_thickness.ply").unwrap();
+            println!("wrote out/hut_wood_thickness.ply");
+            if !report.is_ok() {
+                std::process::exit(1);
+            }
+            return;
+        }
+        Some("drawing") => {
+            let path = std::env::args().nth(2).unwrap_or_else(|| concat!(env!("CARGO_MANIFEST_DIR"), "/hut.svg").to_string());
+            std::fs::write(&path, obj.drawing(&DrawingConfig::default())).unwrap();
+            println!("wrote {}", path);
+            return;
+        }
+        Some("export") => {
+            let resolution = std::env::args().nth(2).map_or(64, |r| r.parse().expect("invalid resolution"));
+            let bodies = obj.material_meshes(&MeshConfig { resolution });
+            std::fs::create_dir_all("out").unwrap();
+            for body in &bodies {
+                let path = format!("out/hut_{}.ply", body.name.to_lowercase());
+                body.mesh.write_ply(&path, body.color).unwrap();
+                println!("wrote {} ({} triangles)", path, body.mesh.triangles.len());
+            }
+            write_gltf("out/hut.gltf", &bodies).unwrap();
+            println!("wrote out/hut.gltf");
+            return;
+        }
+        _ => {}
+    }
+
+    println!("brick_count {}", obj.straw_bricks().len());
+    println!("frame_member_count {}", obj.frame_members().len());
     
     // Example usage:
     // let cfg = Config::default();
@@ -14,3 +143,8 @@ fn main() {
     // Add your debug code here
 }
 
+/// The thin straw walls and frame members keep the default refinement going to `max_depth`,
+/// which did not finish in minutes, so stop at about one percent
+fn coarse_mass_config() -> MassConfig {
+    MassConfig { tolerance: 1e-2, max_depth: 5, ..Default::default() }
+}
@@ -0,0 +1,229 @@
+// Bill of materials: straw bricks, frame members and the bulk clay and reed volumes.
+// `straw_bricks` lists the bricks `wood_sdf` and `straw_sdf` are built from, the frame geometry
+// comes from `frame_segments` and `frame_segments_door` shared with the SDF.
+
+use std::collections::BTreeMap;
+use std::f32::consts::PI;
+use std::fmt::{self, Write as _};
+
+use cgmath::InnerSpace;
+
+use crate::{mass_properties, rotate_z, translate, MassConfig, Material, MyObject, Vector3};
+
+/// Identifies a brick, and the frame around it, within the hut.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum BrickId {
+    /// Brick `index` of `brick_rows[row]`, `mirrored` for the copy on the -y side.
+    Wall { row: usize, index: usize, mirrored: bool },
+    /// Brick `door_bricks[index]`, `mirrored` for the copy on the -y side.
+    Door { index: usize, mirrored: bool },
+}
+
+impl fmt::Display for BrickId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mirrored = |m: bool| if m { " mirrored" } else { "" };
+        match *self {
+            BrickId::Wall { row, index, mirrored: m } => write!(f, "row {} brick {}{}", row, index, mirrored(m)),
+            BrickId::Door { index, mirrored: m } => write!(f, "door {}{}", index, mirrored(m)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StrawBrick {
+    pub id: BrickId,
+    /// Full size along the wall (x), across the wall (y) and vertically (z) in brick coordinates.
+    pub size: Vector3<f32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameMember {
+    pub id: BrickId,
+    /// Endpoints in brick coordinates (after `frame_angle` for door bricks).
+    pub a: Vector3<f32>,
+    pub b: Vector3<f32>,
+    /// Endpoints in world coordinates.
+    pub world: [Vector3<f32>; 2],
+    pub radius: f32,
+}
+
+impl FrameMember {
+    pub fn length(&self) -> f32 {
+        (self.b - self.a).magnitude()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BrickLine {
+    pub length: f32,
+    pub width: f32,
+    pub height: f32,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameLine {
+    pub length: f32,
+    pub radius: f32,
+    pub count: usize,
+}
+
+/// Parts grouped by dimensions (rounded to millimetres), plus bulk volumes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bom {
+    pub bricks: Vec<BrickLine>,
+    pub frame: Vec<FrameLine>,
+    pub clay_volume: f64,
+    pub reed_volume: f64,
+}
+
+fn mm(x: f32) -> i64 {
+    (x * 1000.0).round() as i64
+}
+
+impl MyObject {
+    /// Every straw brick, wall rows first, then the door bricks.
+    pub fn straw_bricks(&self) -> Vec<StrawBrick> {
+        let mut bricks = vec![];
+        let mut push = |id, half_width: f32| {
+            bricks.push(StrawBrick {
+                id,
+                size: Vector3::new(half_width, self.thickness, self.brick_height) * 2.0,
+            });
+        };
+        for (row_i, row) in self.brick_rows.iter().enumerate() {
+            for i in 0..row.count + row.odd as usize {
+                push(BrickId::Wall { row: row_i, index: i, mirrored: false }, self.brick_width);
+                if i < row.count {
+                    push(BrickId::Wall { row: row_i, index: i, mirrored: true }, self.brick_width);
+                }
+            }
+        }
+        for (index, door_brick) in self.door_bricks.iter().enumerate() {
+            for mirrored in [false, true] {
+                push(BrickId::Door { index, mirrored }, door_brick.brick_width);
+            }
+        }
+        bricks
+    }
+
+    /// Every frame member as a cylinder segment, in the order of `straw_bricks`.
+    pub fn frame_members(&self) -> Vec<FrameMember> {
+        let mut members = vec![];
+        let mut push = |id, segments: &[Option<[Vector3<f32>; 2]>], to_world: &dyn Fn(Vector3<f32>) -> Vector3<f32>| {
+            for &[a, b] in segments.iter().flatten() {
+                let world = [to_world(a), to_world(b)];
+                members.push(FrameMember { id, a, b, world, radius: self.frame_thickness });
+            }
+        };
+        let mirror = |p: Vector3<f32>, mirrored: bool| if mirrored { Vector3::new(p.x, -p.y, p.z) } else { p };
+        for (row_i, row) in self.brick_rows.iter().enumerate() {
+            let last = row.count + row.odd as usize - 1;
+            for i in 0..row.count + row.odd as usize {
+                for mirrored in [false, true] {
+                    if mirrored && i >= row.count {
+                        continue;
+                    }
+                    let segments = self.frame_segments(
+                        self.brick_width,
+                        self.brick_width * row.brick_width_factor,
+                        self.brick_height,
+                        self.brick_height * row.brick_height_factor,
+                        !mirrored && i == last,
+                        row_i == 0,
+                    );
+                    let z_angle = self.brick_z_angle(row, i);
+                    push(BrickId::Wall { row: row_i, index: i, mirrored }, &segments, &|p| {
+                        mirror(self.brick_point_to_world(p, z_angle, row.angle), mirrored)
+                    });
+                }
+            }
+        }
+        for (index, door_brick) in self.door_bricks.iter().enumerate() {
+            let segments = self.frame_segments_door(door_brick);
+            let bw = door_brick.brick_width;
+            for mirrored in [false, true] {
+                // Undo the frame rotation around the brick's outer edge, then the brick placement
+                push(BrickId::Door { index, mirrored }, &segments, &|p| {
+                    let p = translate(p, Vector3::new(bw, 0.0, 0.0));
+                    let p = rotate_z(p, -door_brick.frame_angle);
+                    let p = translate(p, Vector3::new(-bw, 0.0, 0.0));
+                    mirror(self.door_brick_point_to_world(p, door_brick.angle, door_brick.shift), mirrored)
+                });
+            }
+        }
+        members
+    }
+
+    /// Bill of materials, the clay and reed volumes are integrated with `cfg`.
+    pub fn bom(&self, cfg: &MassConfig) -> Bom {
+        let mut bricks = BTreeMap::new();
+        for brick in self.straw_bricks() {
+            let s = brick.size;
+            *bricks.entry((mm(s.x), mm(s.y), mm(s.z))).or_insert(0) += 1;
+        }
+        let mut frame = BTreeMap::new();
+        for member in self.frame_members() {
+            *frame.entry((mm(member.length()), mm(member.radius))).or_insert(0) += 1;
+        }
+        let volume = |m| mass_properties(&self.with_material(m), 1.0, cfg).volume;
+
+        Bom {
+            bricks: bricks
+                .into_iter()
+                .map(|((l, w, h), count)| BrickLine {
+                    length: l as f32 / 1000.0,
+                    width: w as f32 / 1000.0,
+                    height: h as f32 / 1000.0,
+                    count,
+                })
+                .collect(),
+            frame: frame
+                .into_iter()
+                .map(|((l, r), count)| FrameLine {
+                    length: l as f32 / 1000.0,
+                    radius: r as f32 / 1000.0,
+                    count,
+                })
+                .collect(),
+            clay_volume: volume(Material::Clay),
+            reed_volume: volume(Material::Reed),
+        }
+    }
+}
+
+impl Bom {
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("part,length,width,height,radius,count,volume\n");
+        for b in &self.bricks {
+            let volume = b.length * b.width * b.height * b.count as f32;
+            writeln!(out, "straw brick,{:.3},{:.3},{:.3},,{},{:.4}", b.length, b.width, b.height, b.count, volume).unwrap();
+        }
+        for f in &self.frame {
+            let volume = PI * f.radius * f.radius * f.length * f.count as f32;
+            writeln!(out, "frame member,{:.3},,,{:.3},{},{:.4}", f.length, f.radius, f.count, volume).unwrap();
+        }
+        writeln!(out, "clay,,,,,,{:.4}", self.clay_volume).unwrap();
+        writeln!(out, "reed,,,,,,{:.4}", self.reed_volume).unwrap();
+        out
+    }
+
+    pub fn to_json(&self) -> String {
+        let bricks = self.bricks.iter()
+            .map(|b| format!(
+                r#"    {{"length": {:.3}, "width": {:.3}, "height": {:.3}, "count": {}}}"#,
+                b.length, b.width, b.height, b.count,
+            ))
+            .collect::<Vec<_>>();
+        let frame = self.frame.iter()
+            .map(|f| format!(r#"    {{"length": {:.3}, "radius": {:.3}, "count": {}}}"#, f.length, f.radius, f.count))
+            .collect::<Vec<_>>();
+        format!(
+            "{{\n  \"straw_bricks\": [\n{}\n  ],\n  \"frame_members\": [\n{}\n  ],\n  \"clay_volume\": {:.4},\n  \"reed_volume\": {:.4}\n}}\n",
+            bricks.join(",\n"),
+            frame.join(",\n"),
+            self.clay_volume,
+            self.reed_volume,
+        )
+    }
+}
@@ -0,0 +1,210 @@
+// Timber cut list for the hut frame: member endpoints are merged into joints, the angles
+// between members meeting at a joint give the end cuts, and members are packed into
+// stock lengths first-fit decreasing.
+
+use std::fmt::Write as _;
+
+use cgmath::InnerSpace;
+
+use crate::bom::FrameMember;
+use crate::{MyObject, Vector3};
+
+#[derive(Debug, Clone)]
+pub struct CutConfig {
+    /// Endpoints closer than this (in world coordinates) belong to the same joint.
+    pub joint_tolerance: f32,
+    /// Length of the timber the members are cut from.
+    pub stock_length: f32,
+    /// Material lost per cut.
+    pub kerf: f32,
+}
+
+impl Default for CutConfig {
+    fn default() -> Self {
+        Self {
+            joint_tolerance: 0.1,
+            stock_length: 3.0,
+            kerf: 0.003,
+        }
+    }
+}
+
+/// Member ends that meet at one point.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Joint {
+    /// Mean of the merged endpoints.
+    pub position: Vector3<f32>,
+    /// `(member, end)` pairs, `end` is 0 for `world[0]` and 1 for `world[1]`.
+    pub ends: Vec<(usize, usize)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cut {
+    /// Index into `CutList::members`.
+    pub member: usize,
+    pub length: f32,
+    /// Joint at each end of the member.
+    pub joints: [usize; 2],
+    /// Angles in degrees to the other members at each end, measured between the members
+    /// pointing away from the joint.
+    pub angles: [Vec<f32>; 2],
+}
+
+/// One stock length and the cuts taken from it, longest first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stock {
+    pub cuts: Vec<usize>,
+    /// Remaining length after the cuts and their kerf.
+    pub offcut: f32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CutList {
+    pub members: Vec<FrameMember>,
+    pub joints: Vec<Joint>,
+    pub cuts: Vec<Cut>,
+    pub stock: Vec<Stock>,
+    /// Cuts longer than the stock length, these are not packed.
+    pub oversize: Vec<usize>,
+    pub stock_length: f32,
+}
+
+impl MyObject {
+    /// Cut list of all `frame_members`.
+    pub fn cut_list(&self, cfg: &CutConfig) -> CutList {
+        let members = self.frame_members();
+        let joints = find_joints(&members, cfg.joint_tolerance);
+
+        let mut joint_of = vec![[0; 2]; members.len()];
+        for (j, joint) in joints.iter().enumerate() {
+            for &(m, end) in &joint.ends {
+                joint_of[m][end] = j;
+            }
+        }
+        let direction = |m: usize, end: usize| {
+            let [a, b] = members[m].world;
+            if end == 0 { (b - a).normalize() } else { (a - b).normalize() }
+        };
+        let cuts = members
+            .iter()
+            .enumerate()
+            .map(|(m, member)| {
+                let angles = [0, 1].map(|end| {
+                    joints[joint_of[m][end]]
+                        .ends
+                        .iter()
+                        .filter(|&&(other, _)| other != m)
+                        .map(|&(other, other_end)| {
+                            let cos = direction(m, end).dot(direction(other, other_end));
+                            cos.clamp(-1.0, 1.0).acos().to_degrees()
+                        })
+                        .collect()
+                });
+                Cut { member: m, length: member.length(), joints: joint_of[m], angles }
+            })
+            .collect::<Vec<_>>();
+
+        let (stock, oversize) = pack(&cuts, cfg.stock_length, cfg.kerf);
+        CutList { members, joints, cuts, stock, oversize, stock_length: cfg.stock_length }
+    }
+}
+
+/// Merge endpoints closer than `tolerance`, transitively.
+fn find_joints(members: &[FrameMember], tolerance: f32) -> Vec<Joint> {
+    let ends = (0..members.len()).flat_map(|m| [(m, 0), (m, 1)]).collect::<Vec<_>>();
+    let point = |(m, end): (usize, usize)| members[m].world[end];
+
+    // Union-find over the endpoints
+    let mut parent = (0..ends.len()).collect::<Vec<_>>();
+    fn root(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+    for i in 0..ends.len() {
+        for j in i + 1..ends.len() {
+            if (point(ends[i]) - point(ends[j])).magnitude() < tolerance {
+                let (ri, rj) = (root(&mut parent, i), root(&mut parent, j));
+                parent[ri.max(rj)] = ri.min(rj);
+            }
+        }
+    }
+
+    let mut joints: Vec<Joint> = vec![];
+    let mut joint_of_root = vec![usize::MAX; ends.len()];
+    for (i, &end) in ends.iter().enumerate() {
+        let r = root(&mut parent, i);
+        if joint_of_root[r] == usize::MAX {
+            joint_of_root[r] = joints.len();
+            joints.push(Joint { position: Vector3::new(0.0, 0.0, 0.0), ends: vec![] });
+        }
+        joints[joint_of_root[r]].ends.push(end);
+    }
+    for joint in &mut joints {
+        let sum = joint.ends.iter().fold(Vector3::new(0.0, 0.0, 0.0), |s, &e| s + point(e));
+        joint.position = sum / joint.ends.len() as f32;
+    }
+    joints
+}
+
+/// First-fit decreasing: each cut, longest first, goes into the first stock length it fits.
+fn pack(cuts: &[Cut], stock_length: f32, kerf: f32) -> (Vec<Stock>, Vec<usize>) {
+    let mut order = (0..cuts.len()).collect::<Vec<_>>();
+    order.sort_by(|&a, &b| cuts[b].length.total_cmp(&cuts[a].length));
+
+    let mut stock: Vec<Stock> = vec![];
+    let mut oversize = vec![];
+    for c in order {
+        let length = cuts[c].length;
+        if length > stock_length {
+            oversize.push(c);
+            continue;
+        }
+        // The last cut of a stock length may go without kerf
+        match stock.iter_mut().find(|s| s.offcut >= length) {
+            Some(s) => {
+                s.cuts.push(c);
+                s.offcut = (s.offcut - length - kerf).max(0.0);
+            }
+            None => stock.push(Stock { cuts: vec![c], offcut: (stock_length - length - kerf).max(0.0) }),
+        }
+    }
+    (stock, oversize)
+}
+
+impl CutList {
+    /// Total length of the offcuts of all used stock lengths.
+    pub fn waste(&self) -> f32 {
+        self.stock.iter().map(|s| s.offcut).sum()
+    }
+
+    /// One line per cut in cutting order, with the world endpoints and the end angles.
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("stock,member,brick,length,x0,y0,z0,x1,y1,z1,angles_start,angles_end\n");
+        let rows = self.stock.iter().enumerate()
+            .flat_map(|(s, stock)| stock.cuts.iter().map(move |&c| (Some(s), c)))
+            .chain(self.oversize.iter().map(|&c| (None, c)));
+        for (stock, c) in rows {
+            let cut = &self.cuts[c];
+            let [a, b] = self.members[cut.member].world;
+            let angles = |angles: &[f32]| {
+                angles.iter().map(|a| format!("{:.1}", a)).collect::<Vec<_>>().join(";")
+            };
+            writeln!(
+                out,
+                "{},{},{},{:.3},{:.3},{:.3},{:.3},{:.3},{:.3},{:.3},{},{}",
+                stock.map_or("oversize".to_string(), |s| (s + 1).to_string()),
+                cut.member,
+                self.members[cut.member].id,
+                cut.length,
+                a.x, a.y, a.z, b.x, b.y, b.z,
+                angles(&cut.angles[0]),
+                angles(&cut.angles[1]),
+            )
+            .unwrap();
+        }
+        out
+    }
+}
@@ -0,0 +1,454 @@
+// Dimensioned construction sheet of the hut, generated from the model.
+//
+// Four views on one SVG sheet: section A-A through the door axis, elevation from the side,
+// plan at `DrawingConfig::plan_height` and section B-B across the porch. Sections and the
+// plan draw the zero contour of every material in its colour, the elevation draws the
+// silhouette of the theory volume. Dimensions are in metres, the sheet in millimetres.
+
+use std::fmt::Write as _;
+
+use press_common::bounds::{AutoBounds, BoundsConfig};
+use press_common::part::{part_slices, Parts};
+use press_common::slice::{Axis, Slice, SlicePlane};
+
+use crate::{Material, MyObject, SDFSurface, Vector3, PI};
+
+/// Margin around the actual extent of the hut in every view, in metres.
+const PADDING: f32 = 0.2;
+
+#[derive(Debug, Clone)]
+pub struct DrawingConfig {
+    /// Drawing scale 1:`scale`.
+    pub scale: f32,
+    /// Sampling step of sections and plan in metres.
+    pub step: f32,
+    /// Sampling step of the elevation silhouette in metres.
+    pub silhouette_step: f32,
+    /// Height of the plan cut above the floor.
+    pub plan_height: f32,
+}
+
+impl Default for DrawingConfig {
+    fn default() -> Self {
+        Self { scale: 50.0, step: 0.025, silhouette_step: 0.05, plan_height: 1.0 }
+    }
+}
+
+const TEXT_SIZE: f32 = 2.5;
+const MARGIN: f32 = 15.0;
+/// Space between views, leaves room for the view titles.
+const GAP: f32 = 20.0;
+
+/// One view being drawn: SVG elements in sheet millimetres relative to the view origin.
+struct View {
+    title: String,
+    /// Sheet millimetres per model metre.
+    k: f32,
+    lo: [f32; 2],
+    hi: [f32; 2],
+    svg: String,
+}
+
+impl View {
+    fn new(title: &str, cfg: &DrawingConfig) -> Self {
+        Self {
+            title: title.to_string(),
+            k: 1000.0 / cfg.scale,
+            lo: [f32::INFINITY; 2],
+            hi: [f32::NEG_INFINITY; 2],
+            svg: String::new(),
+        }
+    }
+
+    /// Sheet position of plane coordinates `[s, t]`, with t pointing up.
+    fn map(&mut self, [s, t]: [f32; 2]) -> [f32; 2] {
+        let q = [s * self.k, -t * self.k];
+        self.include(q, 0.0);
+        q
+    }
+
+    fn include(&mut self, q: [f32; 2], r: f32) {
+        self.lo = [self.lo[0].min(q[0] - r), self.lo[1].min(q[1] - r)];
+        self.hi = [self.hi[0].max(q[0] + r), self.hi[1].max(q[1] + r)];
+    }
+
+    fn polyline(&mut self, points: &[[f32; 2]], style: &str) {
+        let points = points
+            .iter()
+            .map(|&p| {
+                let [x, y] = self.map(p);
+                format!("{:.2},{:.2}", x, y)
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        writeln!(self.svg, r#"<polyline points="{}" {}/>"#, points, style).unwrap();
+    }
+
+    fn line(&mut self, a: [f32; 2], b: [f32; 2], class: &str) {
+        let ([x1, y1], [x2, y2]) = (self.map(a), self.map(b));
+        writeln!(
+            self.svg,
+            r#"<line x1="{:.2}" y1="{:.2}" x2="{:.2}" y2="{:.2}" class="{}"/>"#,
+            x1, y1, x2, y2, class
+        )
+        .unwrap();
+    }
+
+    /// Text at `at`, rotated by `angle` (radians, counter-clockwise in the view).
+    fn text(&mut self, at: [f32; 2], label: &str, anchor: &str, angle: f32) {
+        let [x, y] = self.map(at);
+        self.include([x, y], 0.3 * TEXT_SIZE * label.len() as f32);
+        writeln!(
+            self.svg,
+            r#"<text x="{:.2}" y="{:.2}" text-anchor="{}" transform="rotate({:.1} {:.2} {:.2})">{}</text>"#,
+            x, y, anchor, -angle.to_degrees(), x, y, label
+        )
+        .unwrap();
+    }
+
+    /// Linear dimension between `a` and `b`, its line offset by `offset` metres to the left of a→b.
+    fn dimension(&mut self, a: [f32; 2], b: [f32; 2], offset: f32, label: &str) {
+        let len = ((b[0] - a[0]).powi(2) + (b[1] - a[1]).powi(2)).sqrt();
+        let dir = [(b[0] - a[0]) / len, (b[1] - a[1]) / len];
+        let n = [-dir[1] * offset.signum(), dir[0] * offset.signum()];
+        let at = |p: [f32; 2], d: f32| [p[0] + n[0] * d, p[1] + n[1] * d];
+        let gap = 0.05;
+        let over = 0.1;
+        self.line(at(a, gap), at(a, offset.abs() + over), "thin");
+        self.line(at(b, gap), at(b, offset.abs() + over), "thin");
+        self.line(at(a, offset.abs()), at(b, offset.abs()), "dim");
+        let mid = [(a[0] + b[0]) / 2.0, (a[1] + b[1]) / 2.0];
+        let mut angle = dir[1].atan2(dir[0]);
+        if !(-PI / 2.0 + 1e-3..=PI / 2.0 + 1e-3).contains(&angle) {
+            angle += PI;
+        }
+        // Text sits on the side of the line facing away from the object
+        let text_offset = if n[0] * -angle.sin() + n[1] * angle.cos() > 0.0 { 0.06 } else { 0.06 + TEXT_SIZE / self.k };
+        self.text(at(mid, offset.abs() + text_offset), label, "middle", angle);
+    }
+
+    /// Radius leader to the point of the circle around `centre` at `angle`, drawn `leader`
+    /// metres from outside (positive) or inside (negative) the circle.
+    fn radius(&mut self, centre: [f32; 2], r: f32, angle: f32, leader: f32, label: &str) {
+        let u = [angle.cos(), angle.sin()];
+        let on = [centre[0] + u[0] * r, centre[1] + u[1] * r];
+        let from = [on[0] + u[0] * leader, on[1] + u[1] * leader];
+        self.line(from, on, "dim end");
+        let anchor = if (u[0] * leader.signum()) < 0.0 { "end" } else { "start" };
+        let at = [from[0] + u[0] * leader.signum() * 0.05, from[1] + u[1] * leader.signum() * 0.05];
+        self.text(at, label, anchor, 0.0);
+    }
+
+    /// Arc dimension around `centre` from angle `a0` to `a1` (counter-clockwise).
+    fn arc(&mut self, centre: [f32; 2], r: f32, a0: f32, a1: f32, label: &str) {
+        let point = |a: f32| [centre[0] + r * a.cos(), centre[1] + r * a.sin()];
+        let [x0, y0] = self.map(point(a0));
+        let [x1, y1] = self.map(point(a1));
+        let mid = (a0 + a1) / 2.0;
+        self.map(point(mid));
+        writeln!(
+            self.svg,
+            r#"<path d="M {:.2},{:.2} A {:.2},{:.2} 0 {} 0 {:.2},{:.2}" class="dim start end"/>"#,
+            x0, y0, r * self.k, r * self.k, ((a1 - a0) > PI) as u8, x1, y1
+        )
+        .unwrap();
+        let [tx, ty] = point(mid);
+        let at = [tx + mid.cos() * 0.1, ty + mid.sin() * 0.1];
+        self.text(at, label, if mid.cos() < 0.0 { "end" } else { "start" }, 0.0);
+    }
+
+    fn centre_mark(&mut self, c: [f32; 2]) {
+        let d = 0.15;
+        self.line([c[0] - d, c[1]], [c[0] + d, c[1]], "thin");
+        self.line([c[0], c[1] - d], [c[0], c[1] + d], "thin");
+    }
+
+    /// Zero contours of every material on `plane`, within `[min, max]`.
+    fn materials(&mut self, object: &MyObject, plane: SlicePlane, min: [f32; 2], max: [f32; 2], step: f32) {
+        let slices = part_slices(object, plane, min, max, step);
+        for (part, slice) in object.parts().iter().zip(&slices) {
+            let c = part.color * 0.8;
+            let style = format!(
+                r#"class="outline" style="stroke: rgb({},{},{})""#,
+                (c.x * 255.0).round(),
+                (c.y * 255.0).round(),
+                (c.z * 255.0).round()
+            );
+            for polyline in slice.contours() {
+                self.polyline(&polyline, &style);
+            }
+        }
+    }
+}
+
+impl MyObject {
+    /// The theory volume (all materials and the roof) bounded by its actual extent.
+    ///
+    /// The declared bounding box is sized for the viewer and may clip the roof.
+    fn envelope(&self) -> AutoBounds<MyObject> {
+        let cfg = BoundsConfig { lipschitz: 10.0, ..Default::default() };
+        AutoBounds::new(self.with_material(Material::Theory), &cfg, PADDING)
+    }
+
+    /// Centre of the wall circle in the meridian plane, as (horizontal distance, z).
+    ///
+    /// The wall profile is a circle around a point `root_r` behind the axis and `root_z`
+    /// below the floor, so the centre lies on the opposite side of the axis.
+    fn wall_centre(&self) -> [f32; 2] {
+        [-self.root_r, -self.root_z]
+    }
+
+    /// Horizontal radius of the wall surface at offset `r` from the wall circle, at height `z`.
+    fn wall_radius_at(&self, r: f32, z: f32) -> f32 {
+        (r * r - (z + self.root_z).powi(2)).sqrt() - self.root_r
+    }
+
+    /// Angle of a brick row in the meridian plane, measured at the wall circle centre.
+    fn row_meridian_angle(&self, x_angle: f32) -> f32 {
+        let q = self.brick_point_to_world(Vector3::new(0.0, 0.0, 0.0), 0.0, x_angle);
+        let [cs, cz] = self.wall_centre();
+        (q.z - cz).atan2((q.x * q.x + q.y * q.y).sqrt() - cs)
+    }
+
+    /// Half width of the porch surface at offset `r` from its circle, at `x` and height `z`.
+    fn porch_half_width(&self, r: f32, x: f32, z: f32) -> f32 {
+        (r * r - (z + self.door_root_z + self.door_slope * x).powi(2)).sqrt() - self.door_root_r
+    }
+
+    fn section_view(&self, envelope: &AutoBounds<MyObject>, cfg: &DrawingConfig) -> View {
+        let mut view = View::new("SECTION A-A", cfg);
+        let [lo, hi] = envelope.bounding_box();
+        let plane = SlicePlane::axis(Axis::Y, 0.0);
+        view.materials(self, plane, [lo.x, lo.z], [hi.x, hi.z], cfg.step);
+        view.line([lo.x, 0.0], [hi.x, 0.0], "ground");
+
+        // The back wall lies on -x, mirror the meridian coordinates
+        let [cs, cz] = self.wall_centre();
+        let centre = [-cs, cz];
+        let to_view = |a: f32| PI - a;
+        for (i, row) in self.brick_rows.iter().enumerate() {
+            let a = self.row_meridian_angle(row.angle);
+            let u = [to_view(a).cos(), to_view(a).sin()];
+            let r0 = self.wall_r - self.thickness - 0.3;
+            let r1 = self.wall_r + self.thickness + 0.3;
+            let p0 = [centre[0] + u[0] * r0, centre[1] + u[1] * r0];
+            let p1 = [centre[0] + u[0] * r1, centre[1] + u[1] * r1];
+            view.line(p0, p1, "axis");
+            let label = format!("{} {:.1}°", i + 1, a.to_degrees());
+            view.text([p1[0] + u[0] * 0.05, p1[1] + u[1] * 0.05], &label, "end", 0.0);
+        }
+        let inner = self.wall_r - self.thickness;
+        let outer = self.wall_r + self.thickness;
+        // Above the top row, inner radius from the room and outer radius through the roof
+        view.radius(centre, inner, to_view(1.1), -1.0, &format!("R{:.2}", inner));
+        view.radius(centre, outer, to_view(1.2), 0.6, &format!("R{:.2}", outer));
+
+        let left = lo.x - 0.2;
+        let roof_top = self.top * self.roof_scale;
+        view.dimension([left, 0.0], [left, self.top], 0.4, &format!("{:.2}", self.top));
+        view.dimension([left, 0.0], [left, roof_top], 1.0, &format!("{:.2}", roof_top));
+        view.dimension([0.0, 0.0], [self.door_length, 0.0], -0.5, &format!("{:.2}", self.door_length));
+        let back = -self.wall_radius_at(inner, 0.0);
+        view.dimension([back, 0.0], [0.0, 0.0], -0.5, &format!("{:.2}", -back));
+        view.line([0.0, -0.3], [0.0, roof_top + 0.2], "axis");
+        view
+    }
+
+    fn elevation_view(&self, envelope: &AutoBounds<MyObject>, cfg: &DrawingConfig) -> View {
+        let mut view = View::new("ELEVATION", cfg);
+        let slice = Slice::silhouette(envelope, SlicePlane::axis(Axis::Y, 0.0), cfg.silhouette_step);
+        let contours = slice.contours();
+        let (mut lo, mut hi) = ([f32::INFINITY; 2], [f32::NEG_INFINITY; 2]);
+        for polyline in &contours {
+            view.polyline(polyline, r#"class="outline""#);
+            for p in polyline {
+                lo = [lo[0].min(p[0]), lo[1].min(p[1])];
+                hi = [hi[0].max(p[0]), hi[1].max(p[1])];
+            }
+        }
+        view.line([lo[0] - 0.3, 0.0], [hi[0] + 0.3, 0.0], "ground");
+        view.dimension([lo[0], 0.0], [hi[0], 0.0], -0.5, &format!("{:.2}", hi[0] - lo[0]));
+        view.dimension([hi[0], 0.0], [hi[0], hi[1]], -0.5, &format!("{:.2}", hi[1]));
+        view.line([lo[0] - 0.2, self.roof_bottom], [lo[0] + 0.6, self.roof_bottom], "axis");
+        view.text([lo[0] - 0.25, self.roof_bottom], &format!("+{:.2}", self.roof_bottom), "end", 0.0);
+        view.text(
+            [self.door_length, self.porch_top(self.door_wall_r + self.thickness, self.door_length) + 0.15],
+            &format!("slope {:.0}%", self.door_slope * 100.0),
+            "end",
+            0.0,
+        );
+        view
+    }
+
+    /// Height of the porch surface at offset `r` from its circle, on the porch axis at `x`.
+    fn porch_top(&self, r: f32, x: f32) -> f32 {
+        (r * r - self.door_root_r * self.door_root_r).sqrt() - self.door_root_z - self.door_slope * x
+    }
+
+    fn plan_view(&self, envelope: &AutoBounds<MyObject>, cfg: &DrawingConfig) -> View {
+        let mut view = View::new(&format!("PLAN +{:.2}", cfg.plan_height), cfg);
+        let [lo, hi] = envelope.bounding_box();
+        let z = cfg.plan_height;
+        let plane = SlicePlane::axis(Axis::Z, z);
+        view.materials(self, plane, [lo.x, lo.y], [hi.x, hi.y], cfg.step);
+
+        let outer = self.wall_radius_at(self.wall_r + self.thickness, z);
+        let inner = self.wall_radius_at(self.wall_r - self.thickness, z);
+        view.centre_mark([0.0, 0.0]);
+        view.radius([0.0, 0.0], inner, 0.75 * PI, -0.8, &format!("R{:.2}", inner));
+        view.radius([0.0, 0.0], outer, 1.2 * PI, 0.5, &format!("R{:.2}", outer));
+
+        for a in [-self.door_angle, self.door_angle] {
+            view.line([0.0, 0.0], [outer * a.cos(), outer * a.sin()], "axis");
+        }
+        view.arc(
+            [0.0, 0.0],
+            inner * 0.6,
+            -self.door_angle,
+            self.door_angle,
+            &format!("{:.1}°", (2.0 * self.door_angle).to_degrees()),
+        );
+
+        let half = self.porch_half_width(self.door_wall_r + self.thickness, self.door_length, z);
+        view.dimension(
+            [self.door_length, -half],
+            [self.door_length, half],
+            -0.5,
+            &format!("{:.2}", 2.0 * half),
+        );
+        view.dimension([0.0, lo.y], [self.door_length, lo.y], -0.3, &format!("{:.2}", self.door_length));
+
+        // Section lines
+        view.line([lo.x, 0.0], [hi.x, 0.0], "axis");
+        view.text([lo.x - 0.1, 0.0], "A", "end", 0.0);
+        view.text([hi.x + 0.1, 0.0], "A", "start", 0.0);
+        let xb = self.door_length / 2.0;
+        view.line([xb, lo.y], [xb, hi.y], "axis");
+        view.text([xb, hi.y + 0.1], "B", "middle", 0.0);
+        view.text([xb, lo.y - 0.25], "B", "middle", 0.0);
+        view
+    }
+
+    fn porch_view(&self, envelope: &AutoBounds<MyObject>, cfg: &DrawingConfig) -> View {
+        let x = self.door_length / 2.0;
+        let mut view = View::new("SECTION B-B", cfg);
+        let [lo, hi] = envelope.bounding_box();
+        let plane = SlicePlane::axis(Axis::X, x);
+        let half = self.porch_half_width(self.door_wall_r + self.thickness, x, 0.0) + 0.2;
+        view.materials(self, plane, [-half, lo.z], [half, hi.z], cfg.step);
+        view.line([-half, 0.0], [half, 0.0], "ground");
+
+        // The circle of the +y side of the porch is centred on the -y side
+        let centre = [-self.door_root_r, -self.door_root_z - self.door_slope * x];
+        let inner = self.door_wall_r - self.thickness;
+        let outer = self.door_wall_r + self.thickness;
+        view.radius(centre, inner, 0.35, -0.8, &format!("R{:.2}", inner));
+        view.radius(centre, outer, 0.55, 0.4, &format!("R{:.2}", outer));
+
+        let top = self.porch_top(outer, x);
+        let clear = self.porch_top(inner, x);
+        view.dimension([-half, 0.0], [-half, top], 0.4, &format!("{:.2}", top));
+        view.dimension([0.0, 0.0], [0.0, clear], -0.15, &format!("{:.2}", clear));
+        let w = self.porch_half_width(inner, x, 0.0);
+        view.dimension([-w, 0.0], [w, 0.0], -0.4, &format!("{:.2}", 2.0 * w));
+        view
+    }
+
+    /// Dimensioned construction sheet as SVG: section, elevation, plan and porch section.
+    pub fn drawing(&self, cfg: &DrawingConfig) -> String {
+        let envelope = self.envelope();
+        let views = [
+            [self.section_view(&envelope, cfg), self.elevation_view(&envelope, cfg)],
+            [self.plan_view(&envelope, cfg), self.porch_view(&envelope, cfg)],
+        ];
+        let widths = [0, 1].map(|c| views.iter().map(|row| row[c].hi[0] - row[c].lo[0]).fold(0.0, f32::max));
+        let heights = views.each_ref().map(|row| row.iter().map(|v| v.hi[1] - v.lo[1]).fold(0.0, f32::max));
+
+        let notes = self.drawing_notes(cfg);
+        let width = MARGIN * 2.0 + widths[0] + GAP + widths[1];
+        let notes_height = (notes.len() + 1) as f32 * TEXT_SIZE * 1.5;
+        let height = MARGIN * 2.0 + GAP * 2.0 + heights[0] + heights[1] + notes_height;
+
+        let mut svg = String::new();
+        writeln!(svg, r#"<?xml version="1.0" encoding="UTF-8"?>"#).unwrap();
+        writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w:.0}mm" height="{h:.0}mm" viewBox="0 0 {w:.0} {h:.0}">"#,
+            w = width.ceil(),
+            h = height.ceil()
+        )
+        .unwrap();
+        writeln!(svg, "<!-- Generated from the model by the press-hut drawing command, do not edit -->").unwrap();
+        svg.push_str(STYLE);
+
+        let mut y = MARGIN + GAP;
+        for (row, &row_height) in views.iter().zip(&heights) {
+            let mut x = MARGIN;
+            for (view, &column_width) in row.iter().zip(&widths) {
+                let (ox, oy) = (x - view.lo[0], y - view.lo[1]);
+                writeln!(svg, r#"<text x="{:.2}" y="{:.2}" class="title">{}</text>"#, x, y - GAP / 2.0, view.title)
+                    .unwrap();
+                writeln!(svg, r#"<g transform="translate({:.2} {:.2})">"#, ox, oy).unwrap();
+                svg.push_str(&view.svg);
+                writeln!(svg, "</g>").unwrap();
+                x += column_width + GAP;
+            }
+            y += row_height + GAP;
+        }
+
+        for (i, note) in notes.iter().enumerate() {
+            writeln!(svg, r#"<text x="{:.2}" y="{:.2}">{}</text>"#, MARGIN, y + i as f32 * TEXT_SIZE * 1.5, note).unwrap();
+        }
+        writeln!(svg, "</svg>").unwrap();
+        svg
+    }
+
+    fn drawing_notes(&self, cfg: &DrawingConfig) -> Vec<String> {
+        vec![
+            format!("HUT - scale 1:{:.0}, dimensions in m, angles of brick rows at the wall circle centre", cfg.scale),
+            format!(
+                "wall: R{:.2} around a circle {:.2} from the axis, {:.2} below the floor, thickness {:.2}",
+                self.wall_r, self.root_r, self.root_z, 2.0 * self.thickness
+            ),
+            format!(
+                "porch: R{:.2} around {:.2} from the axis, {:.2} below the floor, length {:.2}, slope {:.2}, opening {:.1}°",
+                self.door_wall_r,
+                self.door_root_r,
+                self.door_root_z,
+                self.door_length,
+                self.door_slope,
+                (2.0 * self.door_angle).to_degrees()
+            ),
+            format!(
+                "roof: scale {:.2} from +{:.2}, top +{:.2}; bricks {:.2} x {:.2} in {} rows, {} door bricks",
+                self.roof_scale,
+                self.roof_bottom,
+                self.top * self.roof_scale,
+                self.brick_width,
+                self.brick_height,
+                self.brick_rows.len(),
+                self.door_bricks.len()
+            ),
+        ]
+    }
+}
+
+const STYLE: &str = r#"<defs>
+<marker id="arrow" viewBox="0 0 10 10" refX="10" refY="5" markerWidth="6" markerHeight="6" orient="auto-start-reverse">
+<path d="M 0 0 L 10 5 L 0 10 z"/>
+</marker>
+</defs>
+<style>
+text { font-family: sans-serif; font-size: 2.5px; }
+.title { font-size: 4px; font-weight: bold; }
+polyline, line, path { fill: none; stroke: black; }
+marker path { fill: black; stroke: none; }
+.outline { stroke-width: 0.35; }
+.ground { stroke-width: 0.5; }
+.dim { stroke-width: 0.18; }
+.thin { stroke-width: 0.13; }
+.axis { stroke-width: 0.13; stroke-dasharray: 4 1 1 1; }
+.start { marker-start: url(#arrow); }
+.end { marker-end: url(#arrow); }
+</style>
+"#;
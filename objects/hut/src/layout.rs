@@ -0,0 +1,349 @@
+// Brick row layout solver: derives `brick_rows` and `door_bricks` from the dome geometry.
+//
+// Bricks are boxes, so neighbours touch first at the corners closest to the rotation axis.
+// Two boxes of half size `s` whose inner corners lie at distance `rho` from the axis, rotated
+// by `step` against each other, leave a gap of `2 rho sin(step / 2) - 2 s cos(step / 2)`.
+// Rows are stacked along the wall profile and bricks spaced around the ring with the
+// smallest step that keeps this gap at the target, then the step is stretched so the ring
+// closes at the back of the hut. Every placement is then checked against its neighbours with
+// `MyObject::contact` and moved (or narrowed, for door bricks) until the gap holds.
+
+use std::fmt;
+
+use crate::bom::BrickId;
+use crate::overlap::{Body, OverlapConfig};
+use crate::{BrickRow, Config, DoorBrick, MyObject, Vector3, PI};
+
+/// A column of door bricks along the porch wall.
+#[derive(Debug, Clone)]
+pub struct DoorColumn {
+    /// Distance of the column's outer edge from the door end, see `DoorBrick::shift`.
+    /// Columns are listed from the door end inwards.
+    pub shift: f32,
+    /// How far the top and bottom frame members reach past the brick towards the dome.
+    pub frame_width: f32,
+    pub frame_angle: f32,
+}
+
+#[derive(Debug, Clone)]
+pub struct LayoutConfig {
+    /// Smallest gap between neighbouring bricks, measured at their closest corners.
+    pub gap: f32,
+    /// Wider joints between neighbouring bricks are reported.
+    pub max_gap: f32,
+    /// Height of the bottom frame member relative to the brick half height.
+    pub brick_height_factor: f32,
+    /// Top of the door opening, the last brick of each door column reaches up to it.
+    pub door_height: f32,
+    pub door_columns: Vec<DoorColumn>,
+}
+
+impl Default for LayoutConfig {
+    fn default() -> Self {
+        Self {
+            gap: 0.02,
+            max_gap: 0.15,
+            brick_height_factor: 1.1,
+            door_height: 2.6,
+            door_columns: vec![
+                DoorColumn { shift: 0.0, frame_width: 0.2, frame_angle: 0.15 },
+                DoorColumn { shift: 0.86, frame_width: 0.5, frame_angle: 0.0 },
+            ],
+        }
+    }
+}
+
+/// Where a remaining gap or overlap is.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Seam {
+    /// Between neighbouring bricks of `brick_rows[row]`.
+    WithinRow { row: usize },
+    /// Between `brick_rows[row]` and the row above it.
+    BetweenRows { row: usize },
+    /// Between the first brick of `brick_rows[row]` and its mirror image across the door.
+    AcrossDoor { row: usize },
+    /// Between neighbouring bricks of door column `column`.
+    WithinDoorColumn { column: usize },
+    /// Between door brick `index` and the wall bricks or the next column.
+    DoorInside { index: usize },
+}
+
+impl fmt::Display for Seam {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Seam::WithinRow { row } => write!(f, "within row {}", row),
+            Seam::BetweenRows { row } => write!(f, "between rows {} and {}", row, row + 1),
+            Seam::AcrossDoor { row } => write!(f, "across the door in row {}", row),
+            Seam::WithinDoorColumn { column } => write!(f, "within door column {}", column),
+            Seam::DoorInside { index } => write!(f, "inside door brick {}", index),
+        }
+    }
+}
+
+/// Gaps left by the layout as measured by `MyObject::contact`, negative values are overlaps.
+#[derive(Debug, Clone)]
+pub struct LayoutReport {
+    pub gaps: Vec<(Seam, f32)>,
+    pub max_gap: f32,
+}
+
+impl LayoutReport {
+    /// Overlaps, and mortar joints wider than `max_gap`. The door opening and the space
+    /// between the porch and the dome are only checked for overlaps.
+    pub fn issues(&self) -> Vec<(Seam, f32)> {
+        self.gaps
+            .iter()
+            .copied()
+            .filter(|&(seam, gap)| {
+                let joint = !matches!(seam, Seam::AcrossDoor { .. } | Seam::DoorInside { .. });
+                gap < 0.0 || (joint && gap > self.max_gap)
+            })
+            .collect()
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.issues().is_empty()
+    }
+}
+
+impl fmt::Display for LayoutReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (joint, gap) in &self.gaps {
+            writeln!(f, "{}: gap {:.3}", joint, gap)?;
+        }
+        for (joint, gap) in self.issues() {
+            if gap < 0.0 {
+                writeln!(f, "warning: overlap of {:.3} {}", -gap, joint)?;
+            } else {
+                writeln!(f, "warning: gap of {:.3} {} exceeds {}", gap, joint, self.max_gap)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Smallest step for which the corner gap (see the top of this file) equals `gap`.
+fn min_step(rho: f32, half: f32, gap: f32) -> f32 {
+    let diagonal = (rho * rho + half * half).sqrt();
+    2.0 * (half.atan2(rho) + (gap / 2.0 / diagonal).clamp(-1.0, 1.0).asin())
+}
+
+/// Contact settings for the placement checks: report gaps up to a metre so they can be
+/// compared with `max_gap`.
+fn contact_config() -> OverlapConfig {
+    OverlapConfig { search_distance: 1.0, ..Default::default() }
+}
+
+/// Smallest separation of `body` from any of `others`, infinite when none is near.
+fn separation(object: &MyObject, body: BrickId, others: impl IntoIterator<Item = BrickId>) -> f32 {
+    let cfg = contact_config();
+    others
+        .into_iter()
+        .filter_map(|other| object.contact(Body::Brick(body), Body::Brick(other), &[], &cfg))
+        .fold(f32::INFINITY, |s, c| s.min(c.separation))
+}
+
+fn wall_bricks(rows: &[BrickRow], row: usize) -> impl Iterator<Item = BrickId> + '_ {
+    let r = &rows[row];
+    (0..r.count + r.odd as usize).flat_map(move |index| {
+        let mirrored = (index < r.count).then_some(BrickId::Wall { row, index, mirrored: true });
+        std::iter::once(BrickId::Wall { row, index, mirrored: false }).chain(mirrored)
+    })
+}
+
+impl Config {
+    /// Replace `brick_rows` and `door_bricks` with a layout derived from the dome geometry.
+    pub fn solve_layout(&mut self, cfg: &LayoutConfig) -> LayoutReport {
+        let mut gaps = vec![];
+        self.brick_rows = self.solve_rows(cfg, &mut gaps);
+        self.door_bricks = self.solve_door(cfg, &self.brick_rows, &mut gaps);
+        LayoutReport { gaps, max_gap: cfg.max_gap }
+    }
+
+    /// The object built from `rows` and `door_bricks`, for the placement checks.
+    fn trial(&self, rows: &[BrickRow], door_bricks: &[DoorBrick]) -> MyObject {
+        MyObject::new(Config { brick_rows: rows.to_vec(), door_bricks: door_bricks.to_vec(), ..self.clone() })
+    }
+
+    /// Row at `angle` with the smallest step allowed by the corner gap, at most `max_half_steps`
+    /// half steps.
+    fn fit_row(&self, cfg: &LayoutConfig, angle: f32, max_half_steps: usize) -> Option<BrickRow> {
+        let (w, h, t) = (self.brick_width, self.brick_height, self.thickness);
+        let r = self.wall_r * angle.cos() - self.root_r;
+        // Horizontal distance of the innermost (top inner) brick corner from the z axis
+        let rho = r - t * angle.cos() - h * angle.sin();
+        if rho <= w {
+            return None;
+        }
+        // Angle around z between the first brick and the back of the hut
+        let span = PI - self.door_angle - self.half_brick_angle;
+        // Half steps between the first brick and the back: an even number puts a brick
+        // on the back, an odd number leaves the back between a brick and its mirror
+        let half_steps = ((2.0 * span / min_step(rho, w, cfg.gap)).floor() as usize).min(max_half_steps);
+        if half_steps == 0 {
+            return None;
+        }
+        let step = 2.0 * span / half_steps as f32;
+        Some(BrickRow {
+            angle,
+            step,
+            count: half_steps.div_ceil(2),
+            odd: half_steps & 1 == 0,
+            // Top members reach one frame thickness past the middle of the joint
+            brick_width_factor: (r * step / 2.0 + self.frame_thickness) / w,
+            brick_height_factor: cfg.brick_height_factor,
+        })
+    }
+
+    fn solve_rows(&self, cfg: &LayoutConfig, gaps: &mut Vec<(Seam, f32)>) -> Vec<BrickRow> {
+        let (h, t) = (self.brick_height, self.thickness);
+        let slack = contact_config().tolerance;
+        // Rows rotate around the center of the wall profile, the inner faces are closest
+        let row_step = min_step(self.wall_r - t, h, cfg.gap);
+        let ground = (self.root_z / self.wall_r).asin();
+
+        let mut rows: Vec<BrickRow> = vec![];
+        let mut angle = ground + (h + cfg.gap) / self.wall_r;
+        'rows: loop {
+            let mut max_half_steps = usize::MAX;
+            let (row, between, within) = loop {
+                let Some(row) = self.fit_row(cfg, angle, max_half_steps) else { break 'rows };
+                let i = rows.len();
+                let candidate = [&rows[..], std::slice::from_ref(&row)].concat();
+                let object = self.trial(&candidate, &[]);
+
+                // Away from the previous row along the wall profile
+                let between = match i.checked_sub(1) {
+                    Some(previous) => wall_bricks(&candidate, i)
+                        .map(|b| separation(&object, b, wall_bricks(&candidate, previous)))
+                        .fold(f32::INFINITY, f32::min),
+                    None => f32::INFINITY,
+                };
+                if between < cfg.gap - slack {
+                    angle += (cfg.gap - between) / self.wall_r + slack / self.wall_r;
+                    continue;
+                }
+
+                // Fewer, wider spaced bricks when neighbours in the row are too close
+                let first = BrickId::Wall { row: i, index: 0, mirrored: false };
+                let second = BrickId::Wall { row: i, index: 1, mirrored: false };
+                let within = if row.count + row.odd as usize > 1 { separation(&object, first, [second]) } else { f32::INFINITY };
+                if within < cfg.gap - slack {
+                    max_half_steps = 2 * row.count - !row.odd as usize - 1;
+                    continue;
+                }
+                break (row, between, within);
+            };
+
+            // The rows end where a brick would meet its mirror image across the door
+            let i = rows.len();
+            let object = self.trial(&[&rows[..], std::slice::from_ref(&row)].concat(), &[]);
+            let first = BrickId::Wall { row: i, index: 0, mirrored: false };
+            let across = separation(&object, first, [BrickId::Wall { row: i, index: 0, mirrored: true }]);
+            if across < cfg.gap - slack {
+                break;
+            }
+
+            if let Some(previous) = i.checked_sub(1) {
+                gaps.push((Seam::BetweenRows { row: previous }, between));
+            }
+            if within.is_finite() {
+                gaps.push((Seam::WithinRow { row: i }, within));
+            }
+            if across.is_finite() {
+                gaps.push((Seam::AcrossDoor { row: i }, across));
+            }
+            rows.push(row);
+            angle += row_step;
+        }
+        rows
+    }
+
+    fn solve_door(&self, cfg: &LayoutConfig, rows: &[BrickRow], gaps: &mut Vec<(Seam, f32)>) -> Vec<DoorBrick> {
+        let (h, t) = (self.brick_height, self.thickness);
+        let slack = contact_config().tolerance;
+        let step = min_step(self.door_wall_r - t, h, cfg.gap);
+        let walls = rows.iter().enumerate().flat_map(|(row, _)| wall_bricks(rows, row)).collect::<Vec<_>>();
+
+        let mut bricks: Vec<DoorBrick> = vec![];
+        for (column_i, column) in cfg.door_columns.iter().enumerate() {
+            // Outer edge of the column, bricks shrink from the inside where something is in the way
+            let outer = self.door_length - column.shift;
+            let limit = match cfg.door_columns.get(column_i + 1) {
+                Some(next) => self.door_length - next.shift,
+                None => f32::NEG_INFINITY,
+            };
+            let first = bricks.len();
+            let mut angle = {
+                // Brick centers sit on the sloped porch wall, the ground is lowest at `outer`
+                let ground = (self.door_root_z + self.door_slope * (outer - self.brick_width)) / self.door_wall_r;
+                ground.asin() + (h + cfg.gap) / self.door_wall_r
+            };
+            let object = self.trial(rows, &[]);
+            loop {
+                let center = object.door_brick_point_to_world(Vector3::new(0.0, 0.0, 0.0), angle, column.shift);
+                if center.z + h > cfg.door_height {
+                    break;
+                }
+                // Stop before the column meets its mirror image over the door
+                let crosses_middle = [-t, t].into_iter().any(|ly| {
+                    object.door_brick_point_to_world(Vector3::new(0.0, ly, h), angle, column.shift).y > -cfg.gap / 2.0
+                });
+                if crosses_middle {
+                    break;
+                }
+
+                let index = bricks.len();
+                let mut brick = DoorBrick {
+                    angle,
+                    shift: 0.0,
+                    brick_width: self.brick_width.min((outer - limit - cfg.gap) / 2.0),
+                    is_bottom: index == first,
+                    frame_width: column.frame_width,
+                    frame_angle: column.frame_angle,
+                    frame_height: 0.0,
+                };
+                // Narrow the brick from the inside until it clears the walls and earlier columns
+                let clearance = loop {
+                    // Keep the outer edge at `outer`, see `transform_door_brick_point`
+                    brick.shift = column.shift - (self.brick_width - brick.brick_width);
+                    // Slivers thinner than the frame are left to the clay
+                    if brick.brick_width < self.frame_thickness {
+                        break None;
+                    }
+                    let object = self.trial(rows, &[&bricks[..], &[brick.clone()]].concat());
+                    let others = walls.iter().copied().chain((0..first).map(|index| BrickId::Door { index, mirrored: false }));
+                    let clearance = separation(&object, BrickId::Door { index, mirrored: false }, others);
+                    if clearance >= cfg.gap - slack {
+                        break Some(clearance);
+                    }
+                    brick.brick_width -= (cfg.gap - clearance) / 2.0 + slack;
+                };
+                let Some(clearance) = clearance else {
+                    angle += step;
+                    continue;
+                };
+
+                if index > first {
+                    let object = self.trial(rows, &[&bricks[..], &[brick.clone()]].concat());
+                    let previous = BrickId::Door { index: index - 1, mirrored: false };
+                    gaps.push((
+                        Seam::WithinDoorColumn { column: column_i },
+                        separation(&object, BrickId::Door { index, mirrored: false }, [previous]),
+                    ));
+                }
+                if clearance.is_finite() {
+                    gaps.push((Seam::DoorInside { index }, clearance));
+                }
+                bricks.push(brick);
+                angle += step;
+            }
+            // The last brick's frame reaches up to the top of the opening
+            if let Some(last) = bricks[first..].last_mut() {
+                let top = object.door_brick_point_to_world(Vector3::new(0.0, 0.0, h), last.angle, column.shift).z;
+                last.frame_height = (cfg.door_height - top).max(0.0);
+            }
+        }
+        bricks
+    }
+}
@@ -5,8 +5,21 @@ pub use cgmath::Vector3;
 pub use cgmath::num_traits::Pow;
 pub use sdf_viewer::sdf::{ffi::set_root_sdf, SDFSample, SDFSurface};
 pub use press_common::{cylinder_between, rotate_x, rotate_z, translate};
+pub use press_common::bvh::Bvh;
+pub use press_common::mass::{mass_properties, MassConfig, MassProperties};
+pub use press_common::mesh::{Mesh, MeshConfig};
+pub use press_common::part::{Part, PartId, Parts};
 pub use std::f32::consts::PI;
 
+pub mod bom;
+pub mod cutlist;
+pub mod drawing;
+pub mod layout;
+pub mod overlap;
+
+use bom::BrickId;
+use overlap::Body;
+
 /// Entry point called when the WASM module is loaded
 #[no_mangle]
 pub extern "C" fn init() {
@@ -15,6 +28,9 @@ pub extern "C" fn init() {
 
 const BOUNDARY: f32 = 0.5;
 
+/// Padding of the brick and frame bounds in the BVHs, covering rounding in the transforms.
+const BVH_MARGIN: f32 = 1e-4;
+
 #[derive(Debug, Clone)]
 pub struct BrickRow {
     pub angle: f32,
@@ -36,7 +52,7 @@ pub struct DoorBrick {
     pub frame_height: f32,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Material {
     Straw,
     Wood,
@@ -46,6 +62,27 @@ pub enum Material {
     All,
 }
 
+impl Material {
+    /// Materials that form separate physical bodies.
+    pub const BODIES: [Material; 4] = [Material::Wood, Material::Straw, Material::Clay, Material::Reed];
+
+    /// Typical bulk density in kg/m³, `None` for the non-physical selections.
+    pub fn density(self) -> Option<f64> {
+        match self {
+            Material::Wood => Some(450.0),
+            Material::Straw => Some(110.0),
+            Material::Clay => Some(1700.0),
+            Material::Reed => Some(150.0),
+            Material::Theory | Material::All => None,
+        }
+    }
+
+    /// Part ID of a physical material, its index in `BODIES`.
+    pub fn part(self) -> Option<PartId> {
+        Material::BODIES.iter().position(|&m| m == self)
+    }
+}
+
 /// Configuration for your object
 #[derive(Debug, Clone)]
 pub struct Config {
@@ -262,33 +299,125 @@ impl Default for Config {
 }
 
 /// Your SDF object
+///
+/// Built from a [`Config`] by [`MyObject::new`]. The geometry is read-only, through the accessors
+/// of the same names: the brick and frame BVHs are derived from it once and would go stale if it
+/// changed.
 #[derive(Debug, Clone)]
 pub struct MyObject {
-    pub root_z: f32,
-    pub root_r: f32,
-    pub wall_r: f32,
-    pub thickness: f32,
-    pub brick_width: f32,
-    pub brick_height: f32,
-    pub door_angle: f32,
-    pub door_root_z: f32,
-    pub door_root_r: f32,
-    pub door_wall_r: f32,
-    pub door_slope: f32,
-    pub door_length: f32,
-    pub roof_scale: f32,
-    pub roof_bottom: f32,
-    pub half_brick_angle: f32,
-    pub top: f32,
-    pub brick_rows: Vec<BrickRow>,
-    pub door_bricks: Vec<DoorBrick>,
-    pub material: Material,
-    pub frame_thickness: f32,
+    pub(crate) root_z: f32,
+    pub(crate) root_r: f32,
+    pub(crate) wall_r: f32,
+    pub(crate) thickness: f32,
+    pub(crate) brick_width: f32,
+    pub(crate) brick_height: f32,
+    pub(crate) door_angle: f32,
+    pub(crate) door_root_z: f32,
+    pub(crate) door_root_r: f32,
+    pub(crate) door_wall_r: f32,
+    pub(crate) door_slope: f32,
+    pub(crate) door_length: f32,
+    pub(crate) roof_scale: f32,
+    pub(crate) roof_bottom: f32,
+    pub(crate) half_brick_angle: f32,
+    pub(crate) top: f32,
+    pub(crate) brick_rows: Vec<BrickRow>,
+    pub(crate) door_bricks: Vec<DoorBrick>,
+    pub(crate) material: Material,
+    pub(crate) frame_thickness: f32,
+    /// Straw bricks by their bounds, built from the fields above by `new`.
+    straw: Bvh<BrickId>,
+    /// Frames around the bricks by their bounds, built from the fields above by `new`.
+    frames: Bvh<BrickId>,
 }
 
+/// The geometry [`MyObject::new`] was given, see [`Config`].
 impl MyObject {
-    fn new(cfg: Config) -> Self {
-        Self {
+    pub fn root_z(&self) -> f32 {
+        self.root_z
+    }
+
+    pub fn root_r(&self) -> f32 {
+        self.root_r
+    }
+
+    pub fn wall_r(&self) -> f32 {
+        self.wall_r
+    }
+
+    pub fn thickness(&self) -> f32 {
+        self.thickness
+    }
+
+    pub fn brick_width(&self) -> f32 {
+        self.brick_width
+    }
+
+    pub fn brick_height(&self) -> f32 {
+        self.brick_height
+    }
+
+    pub fn door_angle(&self) -> f32 {
+        self.door_angle
+    }
+
+    pub fn door_root_z(&self) -> f32 {
+        self.door_root_z
+    }
+
+    pub fn door_root_r(&self) -> f32 {
+        self.door_root_r
+    }
+
+    pub fn door_wall_r(&self) -> f32 {
+        self.door_wall_r
+    }
+
+    pub fn door_slope(&self) -> f32 {
+        self.door_slope
+    }
+
+    pub fn door_length(&self) -> f32 {
+        self.door_length
+    }
+
+    pub fn roof_scale(&self) -> f32 {
+        self.roof_scale
+    }
+
+    pub fn roof_bottom(&self) -> f32 {
+        self.roof_bottom
+    }
+
+    pub fn half_brick_angle(&self) -> f32 {
+        self.half_brick_angle
+    }
+
+    /// Height of the top of the dome, derived from the wall radii and thickness.
+    pub fn top(&self) -> f32 {
+        self.top
+    }
+
+    pub fn brick_rows(&self) -> &[BrickRow] {
+        &self.brick_rows
+    }
+
+    pub fn door_bricks(&self) -> &[DoorBrick] {
+        &self.door_bricks
+    }
+
+    pub fn material(&self) -> Material {
+        self.material
+    }
+
+    pub fn frame_thickness(&self) -> f32 {
+        self.frame_thickness
+    }
+}
+
+impl MyObject {
+    pub fn new(cfg: Config) -> Self {
+        let mut object = Self {
             root_z: cfg.root_z,
             root_r: cfg.root_r,
             wall_r: cfg.wall_r,
@@ -309,7 +438,28 @@ impl MyObject {
             door_bricks: cfg.door_bricks,
             material: cfg.material,
             frame_thickness: cfg.frame_thickness,
-        }
+            straw: Bvh::default(),
+            frames: Bvh::default(),
+        };
+        let pad = |[lo, hi]: [Vector3<f32>; 2]| {
+            let margin = Vector3::new(BVH_MARGIN, BVH_MARGIN, BVH_MARGIN);
+            [lo - margin, hi + margin]
+        };
+        let bricks = object.straw_bricks();
+        let members = object.frame_members();
+        // Bricks are the largest face distance, at least 1/√3 of the Euclidean distance
+        object.straw = Bvh::new(
+            bricks.iter().map(|brick| (brick.id, pad(object.body_box(Body::Brick(brick.id), &[])))),
+            1.0 / 3.0f32.sqrt(),
+        );
+        object.frames = Bvh::new(
+            bricks.iter().map(|brick| {
+                let ends = members.iter().filter(|m| m.id == brick.id).flat_map(|m| m.world);
+                (brick.id, pad(overlap::bounds(ends, object.frame_thickness)))
+            }),
+            1.0,
+        );
+        object
     }
 
     /// Transform a point for brick SDF sampling.
@@ -334,27 +484,39 @@ impl MyObject {
         (p.x.abs() - brick_width).max(p.z.abs() - self.brick_height).max(p.y.abs() - self.thickness)
     }
 
+    /// Frame members around a wall brick as segments in brick coordinates.
+    pub(crate) fn frame_segments(&self, left: f32, right: f32, top: f32, bottom: f32, has_right: bool, has_bottom: bool) -> [Option<[Vector3<f32>; 2]>; 4] {
+        [
+            Some([Vector3::new(right, 0.0, top), Vector3::new(-right, 0.0, top)]),
+            Some([Vector3::new(left, 0.0, top), Vector3::new(left, 0.0, -bottom)]),
+            has_right.then(|| [Vector3::new(-left, 0.0, top), Vector3::new(-left, 0.0, -bottom)]),
+            has_bottom.then(|| [Vector3::new(right, 0.0, -bottom), Vector3::new(-right, 0.0, -bottom)]),
+        ]
+    }
+
     fn frame_sdf(&self, p: Vector3<f32>, left: f32, right: f32, top: f32, bottom: f32, has_right: bool, has_bottom: bool) -> f32 {
-        let mut sdf = std::f32::INFINITY;
-        sdf = sdf.min(cylinder_between(p, Vector3::new(right, 0.0, top), Vector3::new(-right, 0.0, top), self.frame_thickness));
-        sdf = sdf.min(cylinder_between(p, Vector3::new(left, 0.0, top), Vector3::new(left, 0.0, -bottom), self.frame_thickness));
-        if has_right {
-            sdf = sdf.min(cylinder_between(p, Vector3::new(-left, 0.0, top), Vector3::new(-left, 0.0, -bottom), self.frame_thickness));
-        }
-        if has_bottom {
-            sdf = sdf.min(cylinder_between(p, Vector3::new(right, 0.0, -bottom), Vector3::new(-right, 0.0, -bottom), self.frame_thickness));
-        }
-        sdf
+        self.frame_segments(left, right, top, bottom, has_right, has_bottom)
+            .iter()
+            .flatten()
+            .fold(std::f32::INFINITY, |sdf, [a, b]| sdf.min(cylinder_between(p, *a, *b, self.frame_thickness)))
     }
 
-    fn frame_sdf_door(&self, p: Vector3<f32>, left: f32, right: f32, top: f32, bottom: f32, frame_width: f32, frame_height: f32, has_bottom: bool) -> f32 {
-        let mut sdf = std::f32::INFINITY;
-        sdf = sdf.min(cylinder_between(p, Vector3::new(right, 0.0, top), Vector3::new(-right - frame_width, 0.0, top), self.frame_thickness));
-        sdf = sdf.min(cylinder_between(p, Vector3::new(left, 0.0, top + frame_height), Vector3::new(left, 0.0, -bottom), self.frame_thickness));
-        if has_bottom {
-            sdf = sdf.min(cylinder_between(p, Vector3::new(right, 0.0, -bottom), Vector3::new(-right - frame_width, 0.0, -bottom), self.frame_thickness));
-        }
-        sdf
+    /// Frame members around a door brick as segments in (frame rotated) brick coordinates.
+    pub(crate) fn frame_segments_door(&self, door_brick: &DoorBrick) -> [Option<[Vector3<f32>; 2]>; 3] {
+        let (w, h) = (door_brick.brick_width, self.brick_height);
+        let outer = -w - door_brick.frame_width;
+        [
+            Some([Vector3::new(w, 0.0, h), Vector3::new(outer, 0.0, h)]),
+            Some([Vector3::new(w, 0.0, h + door_brick.frame_height), Vector3::new(w, 0.0, -h)]),
+            door_brick.is_bottom.then(|| [Vector3::new(w, 0.0, -h), Vector3::new(outer, 0.0, -h)]),
+        ]
+    }
+
+    fn frame_sdf_door(&self, p: Vector3<f32>, door_brick: &DoorBrick) -> f32 {
+        self.frame_segments_door(door_brick)
+            .iter()
+            .flatten()
+            .fold(std::f32::INFINITY, |sdf, [a, b]| sdf.min(cylinder_between(p, *a, *b, self.frame_thickness)))
     }
 
     fn transform_door_brick_point(&self, p: Vector3<f32>, x_angle: f32, x_shift: f32) -> Vector3<f32> {
@@ -370,114 +532,85 @@ impl MyObject {
         translate(p, Vector3::new(0.0, -self.door_wall_r, 0.0))
     }
 
-    fn wood_sdf(&self, p: Vector3<f32>) -> f32 {
-        let x = p.x;
-        let y = p.y;
-        let z = p.z;
-        let mut sdf = std::f32::INFINITY;
-        for (row_i, row) in self.brick_rows.iter().enumerate() {
-            for i in 0..row.count + row.odd as usize {
-                let z_angle = PI / 2.0 - self.door_angle - self.half_brick_angle - row.step * i as f32;
-
-                // Regular brick
-                let p = self.transform_brick_point(Vector3::new(x, y, z), z_angle, row.angle);
-                sdf = sdf.min(
-                    self.frame_sdf(
-                        p,
-                        self.brick_width,
-                        self.brick_width * row.brick_width_factor,
-                        self.brick_height,
-                        self.brick_height * row.brick_height_factor,
-                        i == row.count + row.odd as usize - 1,
-                        row_i == 0,
-                    )
-                );
-
-                if i < row.count {
-                    // Brick mirrored by x-z plane
-                    let p = self.transform_brick_point(Vector3::new(x, -y, z), z_angle, row.angle);
-                    sdf = sdf.min(
-                        self.frame_sdf(
-                            p,
-                            self.brick_width,
-                            self.brick_width * row.brick_width_factor,
-                            self.brick_height,
-                            self.brick_height * row.brick_height_factor,
-                            false,
-                            row_i == 0,
-                        )
-                    );
-                }
-            }
-        }
+    /// Inverse of `transform_brick_point`: brick coordinates to world coordinates.
+    pub(crate) fn brick_point_to_world(&self, p: Vector3<f32>, z_angle: f32, x_angle: f32) -> Vector3<f32> {
+        let p = translate(p, Vector3::new(0.0, self.wall_r, 0.0));
+        let p = rotate_x(p, x_angle);
+        let p = translate(p, Vector3::new(0.0, -self.root_r, self.root_z));
+        rotate_z(p, -z_angle)
+    }
 
-        for door_brick in self.door_bricks.iter() {
-            let p = self.transform_door_brick_point(Vector3::new(x, y, z), door_brick.angle, door_brick.shift);
-            let p = translate(p, Vector3::new(door_brick.brick_width, 0.0, 0.0));
-            let p = rotate_z(p, door_brick.frame_angle);
-            let p = translate(p, Vector3::new(-door_brick.brick_width, 0.0, 0.0));
-            sdf = sdf.min(
-                self.frame_sdf_door(
-                    p,
-                    door_brick.brick_width,
-                    door_brick.brick_width,
-                    self.brick_height,
-                    self.brick_height,
-                    door_brick.frame_width,
-                    door_brick.frame_height,
-                    door_brick.is_bottom,
-                )
-            );
-            let p = self.transform_door_brick_point(Vector3::new(x, -y, z), door_brick.angle, door_brick.shift);
-            let p = translate(p, Vector3::new(door_brick.brick_width, 0.0, 0.0));
-            let p = rotate_z(p, door_brick.frame_angle);
-            let p = translate(p, Vector3::new(-door_brick.brick_width, 0.0, 0.0));
-            sdf = sdf.min(
-                self.frame_sdf_door(
+    /// Inverse of `transform_door_brick_point`: brick coordinates to world coordinates.
+    pub(crate) fn door_brick_point_to_world(&self, p: Vector3<f32>, x_angle: f32, x_shift: f32) -> Vector3<f32> {
+        let p = translate(p, Vector3::new(0.0, self.door_wall_r, 0.0));
+        let p = rotate_x(p, x_angle);
+        translate(p, -Vector3::new(
+            self.door_length - self.brick_width - x_shift,
+            self.door_root_r,
+            -self.door_root_z - self.door_slope * (self.door_length - self.brick_width - x_shift)
+        ))
+    }
+
+    /// Rotation around z of brick `i` in `row`.
+    pub(crate) fn brick_z_angle(&self, row: &BrickRow, i: usize) -> f32 {
+        PI / 2.0 - self.door_angle - self.half_brick_angle - row.step * i as f32
+    }
+
+    /// Distance to the frame around a single brick, in the brick's coordinates like the
+    /// straw brick itself.
+    pub fn brick_frame_sdf(&self, id: BrickId, p: Vector3<f32>) -> f32 {
+        match id {
+            BrickId::Wall { row: row_i, index, mirrored } => {
+                let row = &self.brick_rows[row_i];
+                let p = if mirrored { Vector3::new(p.x, -p.y, p.z) } else { p };
+                let p = self.transform_brick_point(p, self.brick_z_angle(row, index), row.angle);
+                self.frame_sdf(
                     p,
-                    door_brick.brick_width,
-                    door_brick.brick_width,
+                    self.brick_width,
+                    self.brick_width * row.brick_width_factor,
                     self.brick_height,
-                    self.brick_height,
-                    door_brick.frame_width,
-                    door_brick.frame_height,
-                    door_brick.is_bottom,
+                    self.brick_height * row.brick_height_factor,
+                    !mirrored && index == row.count + row.odd as usize - 1,
+                    row_i == 0,
                 )
-            );
+            }
+            BrickId::Door { index, mirrored } => {
+                let door_brick = &self.door_bricks[index];
+                let p = if mirrored { Vector3::new(p.x, -p.y, p.z) } else { p };
+                let p = self.transform_door_brick_point(p, door_brick.angle, door_brick.shift);
+                let p = translate(p, Vector3::new(door_brick.brick_width, 0.0, 0.0));
+                let p = rotate_z(p, door_brick.frame_angle);
+                let p = translate(p, Vector3::new(-door_brick.brick_width, 0.0, 0.0));
+                self.frame_sdf_door(p, door_brick)
+            }
         }
-
-        sdf
     }
 
-    fn straw_sdf(&self, p: Vector3<f32>) -> f32 {
-        let x = p.x;
-        let y = p.y;
-        let z = p.z;
-        let mut sdf = std::f32::INFINITY;
-        for row in self.brick_rows.iter() {
-            for i in 0..row.count + row.odd as usize {
-                let z_angle = PI / 2.0 - self.door_angle - self.half_brick_angle - row.step * i as f32;
-
-                // Regular brick
-                let p = self.transform_brick_point(Vector3::new(x, y, z), z_angle, row.angle);
-                sdf = sdf.min(self.brick_sdf(p));
-
-                if i < row.count {
-                    // Brick mirrored by x-z plane
-                    let p = self.transform_brick_point(Vector3::new(x, -y, z), z_angle, row.angle);
-                    sdf = sdf.min(self.brick_sdf(p));
-                }
+    /// Distance to a single straw brick.
+    pub fn straw_brick_sdf(&self, id: BrickId, p: Vector3<f32>) -> f32 {
+        match id {
+            BrickId::Wall { row, index, mirrored } => {
+                let row = &self.brick_rows[row];
+                let p = if mirrored { Vector3::new(p.x, -p.y, p.z) } else { p };
+                self.brick_sdf(self.transform_brick_point(p, self.brick_z_angle(row, index), row.angle))
+            }
+            BrickId::Door { index, mirrored } => {
+                let door_brick = &self.door_bricks[index];
+                let p = if mirrored { Vector3::new(p.x, -p.y, p.z) } else { p };
+                let p = self.transform_door_brick_point(p, door_brick.angle, door_brick.shift);
+                self.brick_sdf_smaller(p, door_brick.brick_width)
             }
         }
+    }
 
-        for door_brick in self.door_bricks.iter() {
-            let p = self.transform_door_brick_point(Vector3::new(x, y, z), door_brick.angle, door_brick.shift);
-            sdf = sdf.min(self.brick_sdf_smaller(p, door_brick.brick_width));
-            let p = self.transform_door_brick_point(Vector3::new(x, -y, z), door_brick.angle, door_brick.shift);
-            sdf = sdf.min(self.brick_sdf_smaller(p, door_brick.brick_width));
-        }
+    /// All frames, only those near `p` are evaluated.
+    fn wood_sdf(&self, p: Vector3<f32>) -> f32 {
+        self.frames.distance(p, |&id| self.brick_frame_sdf(id, p))
+    }
 
-        sdf
+    /// All straw bricks, only those near `p` are evaluated.
+    fn straw_sdf(&self, p: Vector3<f32>) -> f32 {
+        self.straw.distance(p, |&id| self.straw_brick_sdf(id, p))
     }
 
     fn distance_from_root(&self, p: Vector3<f32>) -> f32 {
@@ -552,15 +685,47 @@ impl MyObject {
         }
     }
 
-    fn all_sdf_and_material(&self, p: Vector3<f32>) -> (f32, Vector3<f32>) {
+    fn material_sdf(&self, material: Material, p: Vector3<f32>) -> f32 {
+        match material {
+            Material::Wood => { self.wood_sdf(p) },
+            Material::Straw => { self.straw_sdf(p).max(-self.wood_sdf(p)) },
+            Material::Clay => { self.clay_sdf(p) },
+            Material::Theory => { self.theory_sdf(p) },
+            Material::Reed => { self.reed_sdf(p) },
+            Material::All => { self.all_sdf_and_material(p).0 },
+        }
+    }
+
+    fn all_sdf_and_material(&self, p: Vector3<f32>) -> (f32, Material) {
         let sdf = self.wood_sdf(p);
-        if sdf < 0.0 { return (sdf, self.color(Material::Wood)) }
+        if sdf < 0.0 { return (sdf, Material::Wood) }
         let sdf = self.straw_sdf(p);
-        if sdf < 0.0 { return (sdf, self.color(Material::Straw)) }
+        if sdf < 0.0 { return (sdf, Material::Straw) }
         let sdf = self.reed_sdf(p);
-        if sdf < 0.0 { return (sdf, self.color(Material::Reed)) }
+        if sdf < 0.0 { return (sdf, Material::Reed) }
         let sdf = self.theory_sdf_nonroof(p);
-        (sdf, self.color(Material::Clay))
+        (sdf, Material::Clay)
+    }
+}
+
+impl MyObject {
+    /// The same hut showing only `material`.
+    pub fn with_material(&self, material: Material) -> Self {
+        Self { material, ..self.clone() }
+    }
+
+    /// Mass properties of each material body, using `Material::density`.
+    pub fn material_properties(&self, cfg: &MassConfig) -> Vec<(Material, MassProperties)> {
+        let densities = Material::BODIES.map(|m| m.density().unwrap());
+        Material::BODIES
+            .into_iter()
+            .zip(press_common::part::part_mass_properties(self, &densities, cfg))
+            .collect()
+    }
+
+    /// A closed mesh of each material body, named and coloured by material.
+    pub fn material_meshes(&self, cfg: &MeshConfig) -> Vec<press_common::mesh::Body> {
+        press_common::part::part_meshes(self, cfg)
     }
 }
 
@@ -581,18 +746,22 @@ impl SDFSurface for MyObject {
     }
 
     /// Sample the SDF at point p
-    fn sample(&self, p: Vector3<f32>, _distance_only: bool) -> SDFSample {
+    fn sample(&self, p: Vector3<f32>, distance_only: bool) -> SDFSample {
+        // The same cascade without material bookkeeping and shading
+        if distance_only {
+            return SDFSample::new(self.material_sdf(self.material, p), Vector3::new(0.0, 0.0, 0.0));
+        }
+
         let x = p.x;
         let y = p.y;
         let z = p.z;
 
         let (sdf, color) = match self.material {
-            Material::Wood => { (self.wood_sdf(p), self.color(self.material)) },
-            Material::Straw => { (self.straw_sdf(p).max(-self.wood_sdf(p)), self.color(self.material)) },
-            Material::Clay => { (self.clay_sdf(p), self.color(self.material)) },
-            Material::Theory => { (self.theory_sdf(p), self.color(self.material)) },
-            Material::Reed => { (self.reed_sdf(p), self.color(self.material)) },
-            Material::All => { self.all_sdf_and_material(p) },
+            Material::All => {
+                let (sdf, material) = self.all_sdf_and_material(p);
+                (sdf, self.color(material))
+            },
+            material => { (self.material_sdf(material, p), self.color(material)) },
         };
 
         let shade = ((x*x + y*y + z*z).sqrt() / self.door_length / 1.2).powf(3.0);
@@ -603,3 +772,41 @@ impl SDFSurface for MyObject {
         )
     }
 }
+
+/// The physical materials as parts, in the order of `Material::BODIES`.
+impl Parts for MyObject {
+    fn parts(&self) -> Vec<Part> {
+        Material::BODIES
+            .iter()
+            .map(|&m| Part { name: format!("{:?}", m), color: self.color(m) })
+            .collect()
+    }
+
+    fn part_distance(&self, part: PartId, p: Vector3<f32>) -> f32 {
+        self.material_sdf(Material::BODIES[part], p)
+    }
+
+    fn part_distances(&self, p: Vector3<f32>) -> Vec<f32> {
+        let wood = self.wood_sdf(p);
+        let straw = self.straw_sdf(p);
+        Material::BODIES
+            .iter()
+            .map(|m| match m {
+                Material::Wood => wood,
+                Material::Straw => straw.max(-wood),
+                Material::Clay => self.theory_sdf_nonroof(p).max(-straw).max(-wood),
+                _ => self.material_sdf(*m, p),
+            })
+            .collect()
+    }
+
+    /// Follows the viewer: wood, then straw, then reed, then clay.
+    fn part_at(&self, p: Vector3<f32>) -> PartId {
+        let (sdf, material) = self.all_sdf_and_material(p);
+        if sdf < 0.0 {
+            material.part().unwrap()
+        } else {
+            press_common::part::nearest_part(self, p)
+        }
+    }
+}
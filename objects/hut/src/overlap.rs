@@ -0,0 +1,246 @@
+// Overlap and gap detection between the bodies the hut is built from: every straw brick
+// and every frame member is treated separately instead of through the `min` in `straw_sdf`
+// and `wood_sdf`. The separation of two bodies is found as in `press_common::clearance`.
+
+use std::fmt;
+
+use press_common::bounds::{max, min};
+use press_common::clearance::{minimize, Clearance};
+
+use crate::bom::{BrickId, FrameMember};
+use crate::{cylinder_between, Material, MyObject, SDFSample, SDFSurface, Vector3};
+
+#[derive(Debug, Clone)]
+pub struct OverlapConfig {
+    /// Overlaps shallower than this are ignored.
+    pub tolerance: f32,
+    /// Pairs further apart than this are not reported.
+    pub search_distance: f32,
+    /// Seeds per axis of the grid the search starts from.
+    pub seeds: usize,
+}
+
+impl Default for OverlapConfig {
+    fn default() -> Self {
+        Self {
+            tolerance: 1e-3,
+            search_distance: 0.02,
+            seeds: 4,
+        }
+    }
+}
+
+/// A straw brick or a frame member.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Body {
+    Brick(BrickId),
+    /// Member `index` of `frame_members`, belonging to the frame around brick `id`.
+    Frame { id: BrickId, index: usize },
+}
+
+impl Body {
+    pub fn brick_id(&self) -> BrickId {
+        match *self {
+            Body::Brick(id) | Body::Frame { id, .. } => id,
+        }
+    }
+}
+
+impl fmt::Display for Body {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Body::Brick(id) => write!(f, "{}", id),
+            Body::Frame { id, index } => write!(f, "frame member {} of {}", index, id),
+        }
+    }
+}
+
+/// Closest approach of two bodies.
+pub type Contact = Clearance<Body>;
+
+#[derive(Debug, Clone)]
+pub struct OverlapReport {
+    /// Pairs closer than `search_distance`, closest first.
+    pub contacts: Vec<Contact>,
+    pub tolerance: f32,
+}
+
+impl OverlapReport {
+    pub fn overlaps(&self) -> Vec<Contact> {
+        self.contacts.iter().copied().filter(|c| c.separation < -self.tolerance).collect()
+    }
+
+    /// Smallest gap between bodies that do not overlap.
+    pub fn min_gap(&self) -> Option<&Contact> {
+        self.contacts.iter().find(|c| c.separation >= 0.0)
+    }
+}
+
+impl fmt::Display for OverlapReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let overlaps = self.overlaps();
+        writeln!(f, "{} pairs in contact, {} overlapping", self.contacts.len(), overlaps.len())?;
+        for c in &overlaps {
+            writeln!(
+                f,
+                "  {} / {}: overlap {:.4} at ({:.3}, {:.3}, {:.3})",
+                c.a, c.b, -c.separation, c.point.x, c.point.y, c.point.z
+            )?;
+        }
+        if let Some(c) = self.min_gap() {
+            writeln!(f, "smallest gap {:.4} between {} and {}", c.separation, c.a, c.b)?;
+        }
+        Ok(())
+    }
+}
+
+impl MyObject {
+    /// Every straw brick and frame member, `members` are the `frame_members`.
+    pub fn bodies(&self, members: &[FrameMember]) -> Vec<Body> {
+        let bricks = self.straw_bricks().into_iter().map(|brick| Body::Brick(brick.id));
+        let frame = members.iter().enumerate().map(|(index, member)| Body::Frame { id: member.id, index });
+        bricks.chain(frame).collect()
+    }
+
+    /// Distance to a single brick or frame member.
+    pub fn body_sdf(&self, body: Body, members: &[FrameMember], p: Vector3<f32>) -> f32 {
+        match body {
+            Body::Brick(id) => self.straw_brick_sdf(id, p),
+            Body::Frame { index, .. } => {
+                let [a, b] = members[index].world;
+                cylinder_between(p, a, b, members[index].radius)
+            }
+        }
+    }
+
+    /// World bounding box of a single brick or frame member.
+    pub fn body_box(&self, body: Body, members: &[FrameMember]) -> [Vector3<f32>; 2] {
+        let (id, half) = match body {
+            Body::Frame { index, .. } => {
+                let member = &members[index];
+                return bounds(member.world.into_iter(), member.radius);
+            }
+            Body::Brick(id @ BrickId::Wall { .. }) => (id, Vector3::new(self.brick_width, self.thickness, self.brick_height)),
+            Body::Brick(id @ BrickId::Door { index, .. }) => {
+                (id, Vector3::new(self.door_bricks[index].brick_width, self.thickness, self.brick_height))
+            }
+        };
+        let to_world = |p: Vector3<f32>| match id {
+            BrickId::Wall { row, index, mirrored } => {
+                let row = &self.brick_rows[row];
+                let p = self.brick_point_to_world(p, self.brick_z_angle(row, index), row.angle);
+                if mirrored { Vector3::new(p.x, -p.y, p.z) } else { p }
+            }
+            BrickId::Door { index, mirrored } => {
+                let door_brick = &self.door_bricks[index];
+                let p = self.door_brick_point_to_world(p, door_brick.angle, door_brick.shift);
+                if mirrored { Vector3::new(p.x, -p.y, p.z) } else { p }
+            }
+        };
+        let corners = (0..8).map(|c| {
+            to_world(Vector3::new(
+                if c & 1 == 0 { -half.x } else { half.x },
+                if c & 2 == 0 { -half.y } else { half.y },
+                if c & 4 == 0 { -half.z } else { half.z },
+            ))
+        });
+        bounds(corners, 0.0)
+    }
+
+    /// Closest approach of two bodies, `None` when their bounding boxes are further apart
+    /// than `search_distance`.
+    pub fn contact(&self, a: Body, b: Body, members: &[FrameMember], cfg: &OverlapConfig) -> Option<Contact> {
+        let (box_a, box_b) = (self.body_box(a, members), self.body_box(b, members));
+        let margin = Vector3::new(1.0, 1.0, 1.0) * cfg.search_distance;
+        let lo = max(box_a[0], box_b[0]) - margin;
+        let hi = min(box_a[1], box_b[1]) + margin;
+        if lo.x > hi.x || lo.y > hi.y || lo.z > hi.z {
+            return None;
+        }
+        let f = |p| self.body_sdf(a, members, p).max(self.body_sdf(b, members, p));
+        let (point, value) = minimize(f, lo, hi, cfg.seeds, cfg.tolerance / 4.0);
+        Some(Contact { a, b, separation: 2.0 * value, point })
+    }
+
+    /// Closest approach of every pair of bodies within `search_distance`.
+    ///
+    /// Frame members are not compared with each other, they meet at the joints by design,
+    /// nor with the brick they frame.
+    pub fn overlaps(&self, cfg: &OverlapConfig) -> OverlapReport {
+        let members = self.frame_members();
+        let bodies = self.bodies(&members);
+        let mut contacts = vec![];
+        for (i, &a) in bodies.iter().enumerate() {
+            for &b in &bodies[i + 1..] {
+                let frame_a = matches!(a, Body::Frame { .. });
+                let frame_b = matches!(b, Body::Frame { .. });
+                if (frame_a && frame_b) || ((frame_a || frame_b) && a.brick_id() == b.brick_id()) {
+                    continue;
+                }
+                if let Some(contact) = self.contact(a, b, &members, cfg) {
+                    if contact.separation < cfg.search_distance {
+                        contacts.push(contact);
+                    }
+                }
+            }
+        }
+        contacts.sort_by(|a, b| a.separation.total_cmp(&b.separation));
+        OverlapReport { contacts, tolerance: cfg.tolerance }
+    }
+
+    /// Straw and wood with overlapping bodies highlighted, for the viewer or a slice.
+    pub fn overlap_preview(&self, report: &OverlapReport) -> OverlapPreview {
+        OverlapPreview {
+            object: self.clone(),
+            members: self.frame_members(),
+            overlaps: report.overlaps().iter().map(|c| (c.a, c.b)).collect(),
+        }
+    }
+}
+
+pub(crate) fn bounds(points: impl Iterator<Item = Vector3<f32>>, padding: f32) -> [Vector3<f32>; 2] {
+    let pad = Vector3::new(padding, padding, padding);
+    let (lo, hi) = points.fold(
+        (Vector3::new(f32::INFINITY, f32::INFINITY, f32::INFINITY), Vector3::new(f32::NEG_INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY)),
+        |(lo, hi), p| (min(lo, p), max(hi, p)),
+    );
+    [lo - pad, hi + pad]
+}
+
+/// Straw and wood of the hut, bodies involved in an overlap in orange and the overlapping
+/// volume in red.
+#[derive(Debug, Clone)]
+pub struct OverlapPreview {
+    object: MyObject,
+    members: Vec<FrameMember>,
+    overlaps: Vec<(Body, Body)>,
+}
+
+impl SDFSurface for OverlapPreview {
+    fn bounding_box(&self) -> [Vector3<f32>; 2] {
+        self.object.bounding_box()
+    }
+
+    fn sample(&self, p: Vector3<f32>, distance_only: bool) -> SDFSample {
+        let wood = self.object.wood_sdf(p);
+        let straw = self.object.straw_sdf(p);
+        let distance = wood.min(straw);
+        if distance_only || distance >= 0.0 {
+            return SDFSample::new(distance, Vector3::new(0.0, 0.0, 0.0));
+        }
+
+        let mut color = self.object.color(if wood < 0.0 { Material::Wood } else { Material::Straw });
+        for &(a, b) in &self.overlaps {
+            let inside_a = self.object.body_sdf(a, &self.members, p) < 0.0;
+            let inside_b = self.object.body_sdf(b, &self.members, p) < 0.0;
+            if inside_a && inside_b {
+                color = Vector3::new(1.0, 0.0, 0.0);
+                break;
+            }
+            if inside_a || inside_b {
+                color = Vector3::new(1.0, 0.5, 0.0);
+            }
+        }
+        SDFSample::new(distance, color)
+    }
+}
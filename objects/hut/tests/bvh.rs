@@ -0,0 +1,48 @@
+// Bricks and frames through a bounding volume hierarchy, see press_common::bvh
+
+use std::cell::Cell;
+
+use press_common::golden::Field;
+use press_hut::overlap::Body;
+use press_hut::{Bvh, Material, MyObject, SDFSurface, Vector3};
+
+fn grid(hut: &MyObject) -> Vec<Vector3<f32>> {
+    Field::grid(hut, 16).points
+}
+
+#[test]
+fn materials_match_every_brick() {
+    let hut = MyObject::default();
+    let (wood, straw) = (hut.with_material(Material::Wood), hut.with_material(Material::Straw));
+    let ids: Vec<_> = hut.straw_bricks().into_iter().map(|brick| brick.id).collect();
+    for p in grid(&hut) {
+        let frames = ids.iter().fold(f32::INFINITY, |d, &id| d.min(hut.brick_frame_sdf(id, p)));
+        let bricks = ids.iter().fold(f32::INFINITY, |d, &id| d.min(hut.straw_brick_sdf(id, p)));
+        assert_eq!(wood.sample(p, true).distance.to_bits(), frames.to_bits(), "wood at {:?}", p);
+        assert_eq!(straw.sample(p, true).distance.to_bits(), bricks.max(-frames).to_bits(), "straw at {:?}", p);
+    }
+}
+
+#[test]
+fn bricks_far_away_are_skipped() {
+    let hut = MyObject::default();
+    let ids: Vec<_> = hut.straw_bricks().into_iter().map(|brick| brick.id).collect();
+    let bvh = Bvh::new(ids.iter().map(|&id| (id, hut.body_box(Body::Brick(id), &[]))), 1.0 / 3.0f32.sqrt());
+    assert_eq!(bvh.len(), ids.len());
+
+    let points = grid(&hut);
+    let evaluations = Cell::new(0);
+    for &p in &points {
+        let (&nearest, d) = bvh
+            .nearest(p, |&id| {
+                evaluations.set(evaluations.get() + 1);
+                hut.straw_brick_sdf(id, p)
+            })
+            .unwrap();
+        let all = ids.iter().fold(f32::INFINITY, |d, &id| d.min(hut.straw_brick_sdf(id, p)));
+        assert_eq!(d.to_bits(), all.to_bits(), "at {:?}", p);
+        assert_eq!(hut.straw_brick_sdf(nearest, p), d);
+    }
+    let average = evaluations.get() as f32 / points.len() as f32;
+    assert!(average < ids.len() as f32 / 4.0, "{} of {} bricks evaluated per sample", average, ids.len());
+}
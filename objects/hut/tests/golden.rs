@@ -0,0 +1,37 @@
+// Golden-field regression tests, update with PRESS_UPDATE_GOLDEN=1 cargo test -p press-hut
+
+use press_common::golden::{assert_batch, assert_distance_only, assert_golden_field, assert_golden_slice};
+use press_common::slice::{Axis, SlicePlane};
+use press_hut::MyObject;
+
+const TOLERANCE: f32 = 1e-4;
+
+fn snapshot(name: &str) -> String {
+    format!("{}/tests/golden/{}.txt", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+#[test]
+fn hut_field() {
+    assert_golden_field(&MyObject::default(), snapshot("hut"), 24, TOLERANCE);
+}
+
+#[test]
+fn hut_wall_slice() {
+    assert_golden_slice(
+        &MyObject::default(),
+        SlicePlane::axis(Axis::Z, 1.0),
+        0.1,
+        snapshot("hut_wall_slice"),
+        TOLERANCE,
+    );
+}
+
+#[test]
+fn hut_distance_only() {
+    assert_distance_only(&MyObject::default(), 12);
+}
+
+#[test]
+fn hut_batch() {
+    assert_batch(&MyObject::default(), 12);
+}
@@ -0,0 +1,30 @@
+// Distance-property checks, see press_common::validate
+
+use press_common::validate::{validate, ValidationConfig, ValidationReport};
+use press_hut::{Material, MyObject};
+
+fn report(material: Material) -> ValidationReport {
+    let cfg = ValidationConfig { samples: 5000, ..Default::default() };
+    validate(&MyObject::default().with_material(material), &cfg)
+}
+
+// The viewer's `Material::All` cascade jumps from body to body and is not checked
+#[test]
+fn hut_bodies_distance_property() {
+    for material in [Material::Wood, Material::Straw, Material::Clay] {
+        let report = report(material);
+        assert!(report.bounding_box_violations.is_empty(), "{:?}: {}", material, report);
+        assert!(report.sign_violations.is_empty(), "{:?}: {}", material, report);
+        assert!(report.lipschitz < 1.1, "{:?}: {}", material, report);
+    }
+}
+
+#[test]
+fn hut_roof_distance_property() {
+    let report = report(Material::Reed);
+    assert!(report.sign_violations.is_empty(), "{}", report);
+    // The roof samples the outline at `p / roof_scale`, which is not a distance, and reaches
+    // up to 8 cm above the bounding box. Keep both from getting worse.
+    assert!(report.lipschitz < 5.0, "{}", report);
+    assert!(report.bounding_box_violations.iter().all(|v| v.value > -0.08), "{}", report);
+}
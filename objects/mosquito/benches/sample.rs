@@ -0,0 +1,13 @@
+// Sampling cost in batches, run with cargo bench -p press-mosquito
+
+use press_common::bench::compare_batch;
+use press_common::golden::Field;
+use press_mosquito::{Mosquito, Settings};
+
+const RESOLUTION: usize = 24;
+
+fn main() {
+    let mosquito = Mosquito::new(Settings::default());
+    let points = Field::grid(&mosquito, RESOLUTION).points;
+    compare_batch("assembly", &mosquito.assembly(), &points);
+}
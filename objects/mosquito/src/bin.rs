@@ -1,7 +1,32 @@
-use press_mosquito::{Settings, needle_straight};
+use press_common::bounds::{check_bounds, AutoBounds, BoundsConfig};
+use press_common::clearance::ClearanceConfig;
+use press_common::mass::{combine, MassConfig};
+use press_common::mesh::{write_gltf, Mesh, MeshConfig};
+use press_common::outline::OutlineConfig;
+use press_common::part::{part_mass_properties, part_meshes, PartSurface, Parts};
+use press_common::print::{analyze_print, Oriented, PrintConfig};
+use press_common::slice::{Slice, SlicePlane};
+use press_common::thickness::{thickness, ThicknessConfig};
+use press_mosquito::stroke::StrokeConfig;
+use press_mosquito::{Mosquito, MosquitoPart, Needle, Settings, needle_straight};
 
 pub fn main() {
+    let args: Vec<String> = std::env::args().collect();
     let cfg = Settings::default();
+
+    match args.get(1).map(String::as_str) {
+        Some("slice") => return slice(cfg, &args[2..]),
+        Some("bounds") => return bounds(cfg),
+        Some("mass") => return mass(cfg, &args[2..]),
+        Some("export") => return export(cfg, &args[2..]),
+        Some("pattern") => return pattern(cfg, &args[2..]),
+        Some("clearance") => return clearance(cfg, &args[2..]),
+        Some("stroke") => return stroke(cfg, &args[2..]),
+        Some("print") => return print(cfg, &args[2..]),
+        Some("thickness") => return wall_thickness(cfg, &args[2..]),
+        _ => {}
+    }
+
     println!(
         "Hello, world! {} {} {} {}",
         cfg.derived.outer_holder_xmax,
@@ -17,3 +42,164 @@ pub fn main() {
     println!("{:?}", cfg.given.needle_xs2);
 }
 
+/// `slice <axis> <offset> [step]` or `slice <px,py,pz> <nx,ny,nz> [step]`
+///
+/// Writes out/mosquito_slice.png and out/mosquito_slice.svg.
+fn slice(cfg: Settings, args: &[String]) {
+    let (plane_args, step) = match args {
+        [a, b, step] => ([a.clone(), b.clone()], step.parse::<f32>().expect("invalid step")),
+        [a, b] => ([a.clone(), b.clone()], 0.2),
+        _ => panic!("usage: slice <axis> <offset> [step] | slice <px,py,pz> <nx,ny,nz> [step]"),
+    };
+    let plane = SlicePlane::from_args(&plane_args).unwrap_or_else(|e| panic!("{}", e));
+    let slice = Slice::sample(&Mosquito::new(cfg), plane, step);
+    std::fs::create_dir_all("out").unwrap();
+    slice.write("out/mosquito_slice").expect("failed to write slice");
+    println!("{}x{} samples, {} contours", slice.width, slice.height, slice.contours().len());
+}
+
+/// `bounds`: compare the declared bounding boxes with the actual extents
+fn bounds(cfg: Settings) {
+    let bounds_cfg = BoundsConfig::default();
+    println!("Mosquito\n{}", check_bounds(&Mosquito::new(cfg.clone()), &bounds_cfg));
+    println!("Needle\n{}", check_bounds(&Needle::new(cfg), &bounds_cfg));
+}
+
+/// `mass [density]`: volume and weight of the shown parts, density in g/mm³ (default PLA)
+fn mass(cfg: Settings, args: &[String]) {
+    let density = args.first().map_or(1.24e-3, |d| d.parse::<f64>().expect("invalid density"));
+    let mosquito = Mosquito::new(cfg);
+    let parts = mosquito.parts();
+    let props = part_mass_properties(&mosquito, &vec![density; parts.len()], &MassConfig::default());
+    for (part, props) in parts.iter().zip(&props) {
+        if props.volume > 0.0 {
+            println!("{}\n{}", part.name, props);
+        }
+    }
+    println!("Total\n{}", combine(&props));
+}
+
+/// `export [resolution]`: one mesh per part, writes out/mosquito.gltf and out/mosquito_<part>.ply
+fn export(cfg: Settings, args: &[String]) {
+    let resolution = args.first().map_or(128, |r| r.parse().expect("invalid resolution"));
+    let bodies = part_meshes(&Mosquito::new(cfg), &MeshConfig { resolution });
+    std::fs::create_dir_all("out").unwrap();
+    for body in bodies.iter().filter(|b| !b.mesh.triangles.is_empty()) {
+        let path = format!("out/mosquito_{}.ply", body.name.to_lowercase());
+        body.mesh.write_ply(&path, body.color).unwrap();
+        println!("wrote {} ({} triangles)", path, body.mesh.triangles.len());
+    }
+    write_gltf("out/mosquito.gltf", &bodies).unwrap();
+    println!("wrote out/mosquito.gltf");
+}
+
+/// `pattern [step]`: flat outlines of the needle plates, writes out/mosquito_<part>.dxf and .svg,
+/// numbered out/mosquito_<part>_<plate>.* when a part has several plates
+fn pattern(cfg: Settings, args: &[String]) {
+    let step = args.first().map_or(0.1, |s| s.parse::<f32>().expect("invalid step"));
+    let mosquito = Mosquito::new(cfg);
+    std::fs::create_dir_all("out").unwrap();
+    for part in MosquitoPart::PLATES {
+        let plates = mosquito.plates(part);
+        for (k, &z) in plates.iter().enumerate() {
+            let outline = mosquito.flat_pattern(part, z, &OutlineConfig { step, ..Default::default() }).unwrap();
+            let name = format!("out/mosquito_{}", part.part().name.to_lowercase());
+            let name = if plates.len() == 1 { name } else { format!("{}_{}", name, k) };
+            outline.write_dxf(format!("{}.dxf", name), "CUT").unwrap();
+            outline.write_svg(format!("{}.svg", name), 5.0).unwrap();
+            let [lo, hi] = outline.bounds();
+            println!(
+                "wrote {}.dxf and .svg: {} loops, {:.1} x {:.1} mm, cut length {:.0} mm",
+                name,
+                outline.polylines.len(),
+                hi[0] - lo[0],
+                hi[1] - lo[1],
+                outline.length()
+            );
+        }
+    }
+}
+
+/// `clearance [min]`: gaps between the parts, fails below `min` mm (default 1)
+fn clearance(cfg: Settings, args: &[String]) {
+    let min_clearance = args.first().map_or(1.0, |s| s.parse::<f32>().expect("invalid clearance"));
+    let report = Mosquito::new(cfg).clearances(&ClearanceConfig { min_clearance, ..Default::default() });
+    print!("{}", report);
+    if !report.is_ok() {
+        std::process::exit(1);
+    }
+}
+
+/// `stroke [angle] [steps]`: teeth overlap and collisions along the stroke, writes
+/// out/mosquito_stroke.png
+fn stroke(cfg: Settings, args: &[String]) {
+    let mut stroke_cfg = StrokeConfig { angle: cfg.given.penetration_angle, ..Default::default() };
+    if let Some(angle) = args.first() {
+        stroke_cfg.angle = angle.parse().expect("invalid angle");
+    }
+    if let Some(steps) = args.get(1) {
+        stroke_cfg.steps = steps.parse().expect("invalid steps");
+    }
+    let mosquito = Mosquito::new(cfg);
+    print!("{}", mosquito.stroke(&stroke_cfg));
+    std::fs::create_dir_all("out").unwrap();
+    mosquito.write_stroke_png(&stroke_cfg, 1.0, "out/mosquito_stroke.png").unwrap();
+    println!("wrote out/mosquito_stroke.png");
+}
+
+/// `print [part] [angle]`: print orientation of the printed parts (or one part) with overhangs
+/// beyond `angle` degrees from vertical (default 45), writes out/mosquito_<part>_print.ply
+fn print(cfg: Settings, args: &[String]) {
+    let parts: Vec<MosquitoPart> = match args.first() {
+        Some(name) => vec![MosquitoPart::from_name(name).unwrap_or_else(|| panic!("unknown part {}", name))],
+        None => MosquitoPart::ALL.into_iter().filter(|part| !MosquitoPart::PLATES.contains(part)).collect(),
+    };
+    let mut print_cfg = PrintConfig::default();
+    if let Some(angle) = args.get(1) {
+        print_cfg.overhang_angle = angle.parse().expect("invalid angle");
+    }
+    let assembly = Mosquito::new(cfg).assembly();
+    std::fs::create_dir_all("out").unwrap();
+    for part in parts {
+        let surface = AutoBounds::new(PartSurface::new(&assembly, part.id()), &BoundsConfig::default(), 1.0);
+        let report = analyze_print(&surface, &print_cfg);
+        println!("{:?}\n{}", part, report);
+        let best = report.best();
+        let path = format!("out/mosquito_{}_print.ply", part.part().name.to_lowercase());
+        Mesh::extract(&Oriented::new(surface, best), &print_cfg.mesh).write_ply(&path, part.color()).unwrap();
+        println!(
+            "wrote {}: down ({:.3}, {:.3}, {:.3}), {}",
+            path,
+            best.down.x,
+            best.down.y,
+            best.down.z,
+            if report.is_support_free(print_cfg.overhang_tolerance) { "support-free" } else { "needs support" }
+        );
+    }
+}
+
+/// `thickness [min] [part]`: walls of the printed parts (or one part) thinner than `min` mm
+/// (default 1), writes out/mosquito_<part>_thickness.ply coloured by thickness
+fn wall_thickness(cfg: Settings, args: &[String]) {
+    let min_thickness = args.first().map_or(1.0, |s| s.parse::<f32>().expect("invalid thickness"));
+    let parts: Vec<MosquitoPart> = match args.get(1) {
+        Some(name) => vec![MosquitoPart::from_name(name).unwrap_or_else(|| panic!("unknown part {}", name))],
+        None => MosquitoPart::ALL.into_iter().filter(|part| !MosquitoPart::PLATES.contains(part)).collect(),
+    };
+    let thickness_cfg = ThicknessConfig { min_thickness, ..Default::default() };
+    let assembly = Mosquito::new(cfg).assembly();
+    std::fs::create_dir_all("out").unwrap();
+    let mut ok = true;
+    for part in parts {
+        let surface = AutoBounds::new(PartSurface::new(&assembly, part.id()), &BoundsConfig::default(), 1.0);
+        let report = thickness(&surface, &thickness_cfg);
+        println!("{:?}\n{}", part, report);
+        let path = format!("out/mosquito_{}_thickness.ply", part.part().name.to_lowercase());
+        report.write_ply(&path).unwrap();
+        println!("wrote {}", path);
+        ok &= report.is_ok();
+    }
+    if !ok {
+        std::process::exit(1);
+    }
+}
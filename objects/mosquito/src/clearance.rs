@@ -0,0 +1,41 @@
+//! Clearances between the separately made parts of the press, see `press_common::clearance`.
+//!
+//! The check runs on the whole assembly whatever the `show_*` settings. The needles sit in
+//! pockets of their holders and the connector is bolted to the inner holder, those pairs touch
+//! by design and are left out.
+
+use press_common::clearance::{clearances, ClearanceConfig, ClearanceReport};
+
+use crate::{Mosquito, MosquitoPart};
+
+impl MosquitoPart {
+    /// Pairs of parts mounted on each other.
+    pub const MOUNTED: [(MosquitoPart, MosquitoPart); 3] = [
+        (MosquitoPart::InnerNeedle, MosquitoPart::InnerHolder),
+        (MosquitoPart::OuterNeedle, MosquitoPart::OuterHolder),
+        (MosquitoPart::InnerHolder, MosquitoPart::Connector),
+    ];
+}
+
+impl Mosquito {
+    /// The press with every part shown and the needle pocket cut into the inner holder.
+    pub fn assembly(&self) -> Mosquito {
+        let mut cfg = self.cfg.clone();
+        let given = &mut cfg.given;
+        given.show_outer_holder1 = true;
+        given.show_outer_holder2 = true;
+        given.show_inner_holder = true;
+        given.show_inner_hole = true;
+        given.show_connector = true;
+        given.show_inner_needle = true;
+        given.show_outer_needle = true;
+        Mosquito::new(cfg)
+    }
+
+    /// Closest approach of every pair of parts of the assembly except the mounted ones.
+    pub fn clearances(&self, cfg: &ClearanceConfig) -> ClearanceReport {
+        let mut cfg = cfg.clone();
+        cfg.ignore.extend(MosquitoPart::MOUNTED.iter().map(|&(a, b)| (a.id(), b.id())));
+        clearances(&self.assembly(), &cfg)
+    }
+}
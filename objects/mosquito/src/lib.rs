@@ -3,10 +3,34 @@ use std::f64::INFINITY;
 pub use cgmath::Vector3;
 pub use sdf_viewer::sdf::{ffi::set_root_sdf, SDFSample, SDFSurface};
 pub use press_common::create_computation;
+pub use press_common::part::{Part, PartId, Parts};
 
+pub mod clearance;
+pub mod pattern;
+pub mod profile;
+pub mod stroke;
+
+use profile::Profile;
+
+/// Exports the object named by `PRESS_PART` at build time, the whole press when unset.
 #[no_mangle]
 pub extern "C" fn init() {
-    set_root_sdf(Box::new(Mosquito { cfg: Settings::default() }));
+    set_root_sdf(root_sdf(Settings::default(), option_env!("PRESS_PART")));
+}
+
+/// The object for a `PRESS_PART` name: `needle`, `inner_needle`, `outer_needle`,
+/// `inner_holder`, `outer_holder` or `connector`.
+pub fn root_sdf(cfg: Settings, part: Option<&str>) -> Box<dyn SDFSurface + Send + Sync> {
+    match part.unwrap_or("") {
+        "" | "mosquito" => Box::new(Mosquito::new(cfg)),
+        "needle" => Box::new(Needle::new(cfg)),
+        "inner_needle" => Box::new(InnerNeedle::new(cfg)),
+        "outer_needle" => Box::new(OuterNeedle::new(cfg)),
+        name => {
+            let part = MosquitoPart::from_name(name).unwrap_or_else(|| panic!("unknown PRESS_PART {}", name));
+            Box::new(Mosquito::new(cfg).with_parts(&[part]))
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -35,11 +59,23 @@ pub struct GivenSettings {
     pub inner_holder_height: f64,
     pub inner_holder_xmin: f64,
 
+    /// Tooth outline, `None` for the trapezoid stack of `needle_ys`, `needle_xs1` and
+    /// `needle_xs2`, see [`Profile::trapezoid_stack`].
+    pub needle_profile: Option<Profile>,
     pub needle_ys: Vec<f64>,
     pub needle_xs1: Vec<f64>,
     pub needle_xs2: Vec<f64>,
     pub needle_cut_y: f64,
     pub needle_cut_r: f64,
+    /// More tooth outlines for [`NeedlePlacement::profile`], from index 1 on.
+    pub needle_profiles: Vec<Profile>,
+    /// `None` for `needle_count - 1` needles `needle_distance` apart on one plate at x = z = 0.
+    /// Pass [`NeedlePlacement::grid`] with `needle_count_z - 1` plates `2 * needle_distance_z`
+    /// apart for the full stack.
+    pub inner_needles: Option<Vec<NeedlePlacement>>,
+    /// `None` for `needle_count` needles on one plate at z = `-needle_distance_z`, each half a
+    /// step before an inner one. The full stack has `needle_count_z` plates.
+    pub outer_needles: Option<Vec<NeedlePlacement>>,
 
     pub show_outer_holder1: bool,
     pub show_outer_holder2: bool,
@@ -51,6 +87,47 @@ pub struct GivenSettings {
     pub show_outer_needle: bool,
 }
 
+/// Where one needle sits.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NeedlePlacement {
+    /// Offset across the straight needles from the first inner needle, before bending.
+    pub x: f64,
+    /// Mid-plane of the needle's plate, needles with the same `z` are cut from one plate.
+    pub z: f64,
+    /// Tooth outline, an index into `DerivedSettings::needle_profiles`.
+    pub profile: usize,
+    /// Bend angle of this needle, `penetration_angle` when `None`.
+    pub angle: Option<f64>,
+}
+
+impl NeedlePlacement {
+    /// `count` needles `distance` apart from `x0` on each of `plates` plates `distance_z`
+    /// apart from `z0`, all with the first profile.
+    pub fn grid(x0: f64, distance: f64, count: usize, z0: f64, distance_z: f64, plates: usize) -> Vec<Self> {
+        (0..plates)
+            .flat_map(|k| {
+                (0..count).map(move |i| NeedlePlacement {
+                    x: x0 + distance * i as f64,
+                    z: z0 + distance_z * k as f64,
+                    profile: 0,
+                    angle: None,
+                })
+            })
+            .collect()
+    }
+}
+
+/// The distinct heights of `heights` in order.
+fn distinct(heights: impl IntoIterator<Item = f64>) -> Vec<f64> {
+    let mut plates: Vec<f64> = vec![];
+    for z in heights {
+        if !plates.contains(&z) {
+            plates.push(z);
+        }
+    }
+    plates
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct DerivedSettings {
     pub needle_distance_x_diag: f64,
@@ -62,6 +139,20 @@ pub struct DerivedSettings {
     pub outer_holder_zmin: f64,
     pub needle_width: f64,
     pub needle_halfwidth: f64,
+    /// `needle_profile` (or its trapezoid stack) followed by `needle_profiles`.
+    pub needle_profiles: Vec<Profile>,
+    pub inner_needles: Vec<NeedlePlacement>,
+    pub outer_needles: Vec<NeedlePlacement>,
+    /// Mid-planes of the plates the needles are on.
+    pub inner_plates: Vec<f64>,
+    pub outer_plates: Vec<f64>,
+    /// Mid-planes the holders have slots for: the `needle_count_z` stack and any other plate.
+    pub inner_slots: Vec<f64>,
+    pub outer_slots: Vec<f64>,
+    /// Outermost needle centres, the ends of the needle handles.
+    pub inner_needle_xmin: f64,
+    pub outer_needle_xmin: f64,
+    pub outer_needle_xmax: f64,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -97,6 +188,7 @@ impl Default for GivenSettings {
             inner_holder_height: 130.0,
             inner_holder_xmin: -40.0,
 
+            needle_profile: None,
             needle_ys: vec![
                 8.5,
                 14.0,
@@ -120,6 +212,9 @@ impl Default for GivenSettings {
             ],
             needle_cut_y: 0.6,
             needle_cut_r: 0.4,
+            needle_profiles: vec![],
+            inner_needles: None,
+            outer_needles: None,
 
             show_outer_holder1: false,
             show_outer_holder2: false,
@@ -136,34 +231,69 @@ impl Default for GivenSettings {
 impl DerivedSettings {
     fn new(given: &GivenSettings) -> Self {
         let needle_distance_x_diag = given.needle_distance / 2.0;
-        let needle_width = *given.needle_xs2.last().unwrap();
+        let needle_profile = given.needle_profile.clone().unwrap_or_else(|| {
+            Profile::trapezoid_stack(
+                &given.needle_ys,
+                &given.needle_xs1,
+                &given.needle_xs2,
+                given.needle_cut_y,
+                given.needle_cut_r,
+            )
+        });
+        let needle_width = needle_profile.top_width();
         let needle_halfwidth = needle_width / 2.0;
-        let outer_holder_xmin = - needle_distance_x_diag - needle_halfwidth;
-        let outer_holder_zmin = - given.needle_distance_z - given.hill_zshift_outer;
+        let needle_profiles: Vec<Profile> =
+            std::iter::once(needle_profile).chain(given.needle_profiles.iter().cloned()).collect();
+
+        let inner_needles = given.inner_needles.clone().unwrap_or_else(|| {
+            NeedlePlacement::grid(0.0, given.needle_distance, given.needle_count - 1, 0.0, 0.0, 1)
+        });
+        let outer_needles = given.outer_needles.clone().unwrap_or_else(|| {
+            NeedlePlacement::grid(
+                -needle_distance_x_diag, given.needle_distance, given.needle_count,
+                -given.needle_distance_z, 0.0, 1,
+            )
+        });
+        let inner_plates = distinct(inner_needles.iter().map(|n| n.z));
+        let outer_plates = distinct(outer_needles.iter().map(|n| n.z));
+        let stack = |z0: f64, count: usize| (0..count).map(move |i| z0 + given.needle_distance_z * i as f64 * 2.0);
+        let inner_slots = distinct(stack(0.0, given.needle_count_z - 1).chain(inner_plates.iter().copied()));
+        let outer_slots =
+            distinct(stack(-given.needle_distance_z, given.needle_count_z).chain(outer_plates.iter().copied()));
+
+        let halfwidth = |n: &NeedlePlacement| needle_profiles[n.profile].top_width() / 2.0;
+        let xs = |needles: &[NeedlePlacement]| needles.iter().map(|n| n.x).collect::<Vec<_>>();
+        let (inner_xs, outer_xs) = (xs(&inner_needles), xs(&outer_needles));
+        let outer_holder_xmin = outer_needles.iter().map(|n| n.x - halfwidth(n)).fold(f64::INFINITY, f64::min);
+        let outer_holder_xmax = outer_needles.iter().map(|n| n.x + halfwidth(n)).fold(f64::NEG_INFINITY, f64::max);
         Self {
             needle_distance_x_diag,
             inner_needle_x: given.first_needle_x + needle_distance_x_diag,
-            inner_holder_xmax:
-                given.needle_distance * (given.needle_count - 2) as f64 + given.hill_xshift,
+            inner_holder_xmax: inner_xs.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b)) + given.hill_xshift,
             needle_width,
             needle_halfwidth,
             outer_holder_xmin,
-            outer_holder_xmax:
-                outer_holder_xmin
-                    + given.needle_distance * (given.needle_count - 1) as f64
-                    + needle_width,
-            outer_holder_zmin,
+            outer_holder_xmax,
+            outer_holder_zmin:
+                outer_slots.iter().fold(f64::INFINITY, |a, &b| a.min(b)) - given.hill_zshift_outer,
             outer_holder_zmax:
-                - given.needle_distance_z
-                    + given.needle_distance_z * 2.0 * (given.needle_count_z - 1) as f64
-                    + given.hill_zshift_outer,
+                outer_slots.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b)) + given.hill_zshift_outer,
+            inner_needle_xmin: inner_xs.iter().fold(f64::INFINITY, |a, &b| a.min(b)),
+            outer_needle_xmin: outer_xs.iter().fold(f64::INFINITY, |a, &b| a.min(b)),
+            outer_needle_xmax: outer_xs.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b)),
+            needle_profiles,
+            inner_needles,
+            outer_needles,
+            inner_plates,
+            outer_plates,
+            inner_slots,
+            outer_slots,
         }
     }
 }
 
-impl Default for Settings {
-    fn default() -> Self {
-        let given = GivenSettings::default();
+impl Settings {
+    pub fn new(given: GivenSettings) -> Self {
         Self {
             derived: DerivedSettings::new(&given),
             given,
@@ -171,6 +301,12 @@ impl Default for Settings {
     }
 }
 
+impl Default for Settings {
+    fn default() -> Self {
+        Self::new(GivenSettings::default())
+    }
+}
+
 fn xcos_outer(cfg: &Settings, x: f64) -> f64 {
     let x = x + cfg.derived.needle_distance_x_diag;
     -(x * 2.0 * std::f64::consts::PI / cfg.given.needle_distance).cos()
@@ -254,11 +390,8 @@ create_computation! {
             .max(
                 (slf.y - slf.cfg.given.outer_needletop_width)
                     .min(
-                        (-slf.x - slf.cfg.derived.needle_distance_x_diag)
-                            .max(
-                                slf.x - slf.cfg.derived.outer_holder_xmax
-                                    + slf.cfg.derived.needle_halfwidth
-                            )
+                        (slf.cfg.derived.outer_needle_xmin - slf.x)
+                            .max(slf.x - slf.cfg.derived.outer_needle_xmax)
                     )
             )
     }
@@ -284,9 +417,9 @@ fn outer_holder(comp: &Computation) -> f64 {
                 ((comp.x + comp.cfg.given.first_needle_x).powi(2) + comp.y.powi(2)).sqrt()
                 - outer_r;
 
+            // Room for an inner holder at each inner needle slot
             let mut outer_circle_z = INFINITY;
-            for i in 0..comp.cfg.given.needle_count_z - 1 {
-                let zp = comp.cfg.given.needle_distance_z * i as f64 * 2.0;
+            for &zp in &comp.cfg.derived.inner_slots {
                 outer_circle_z = outer_circle_z
                     .min(
                         (-comp.z - comp.cfg.given.thickness - comp.cfg.given.holder_gap_z + zp)
@@ -308,11 +441,132 @@ fn outer_holder(comp: &Computation) -> f64 {
     )
 }
 
+/// The separately made parts of the press.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MosquitoPart {
+    InnerNeedle,
+    OuterNeedle,
+    InnerHolder,
+    OuterHolder,
+    Connector,
+}
+
+impl MosquitoPart {
+    /// All parts, a part's ID is its index.
+    pub const ALL: [MosquitoPart; 5] = [
+        MosquitoPart::InnerNeedle,
+        MosquitoPart::OuterNeedle,
+        MosquitoPart::InnerHolder,
+        MosquitoPart::OuterHolder,
+        MosquitoPart::Connector,
+    ];
+
+    /// The part named like `inner_holder` (or `InnerHolder`), case and underscores ignored.
+    pub fn from_name(name: &str) -> Option<MosquitoPart> {
+        let name = name.replace('_', "").to_lowercase();
+        MosquitoPart::ALL.into_iter().find(|part| format!("{:?}", part).to_lowercase() == name)
+    }
+
+    /// Part ID, its index in `ALL`.
+    pub fn id(self) -> PartId {
+        MosquitoPart::ALL.iter().position(|&p| p == self).unwrap()
+    }
+
+    pub fn part(self) -> Part {
+        Part { name: format!("{:?}", self), color: self.color() }
+    }
+
+    pub fn color(self) -> Vector3<f32> {
+        match self {
+            MosquitoPart::InnerNeedle => Vector3::new(0.7, 0.7, 0.8),
+            MosquitoPart::OuterNeedle => Vector3::new(0.5, 0.5, 0.6),
+            MosquitoPart::InnerHolder => Vector3::new(1.0, 1.0, 0.0),
+            MosquitoPart::OuterHolder => Vector3::new(1.0, 0.6, 0.0),
+            MosquitoPart::Connector => Vector3::new(0.2, 0.6, 1.0),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Mosquito {
     cfg: Settings,
 }
 
+impl Mosquito {
+    pub fn new(cfg: Settings) -> Self {
+        Self { cfg }
+    }
+
+    pub fn settings(&self) -> &Settings {
+        &self.cfg
+    }
+
+    /// The press showing exactly `parts`, the inner hole as set.
+    pub fn with_parts(&self, parts: &[MosquitoPart]) -> Mosquito {
+        let mut cfg = self.cfg.clone();
+        let given = &mut cfg.given;
+        given.show_inner_needle = parts.contains(&MosquitoPart::InnerNeedle);
+        given.show_outer_needle = parts.contains(&MosquitoPart::OuterNeedle);
+        given.show_inner_holder = parts.contains(&MosquitoPart::InnerHolder);
+        given.show_outer_holder1 = parts.contains(&MosquitoPart::OuterHolder);
+        given.show_outer_holder2 = parts.contains(&MosquitoPart::OuterHolder);
+        given.show_connector = parts.contains(&MosquitoPart::Connector);
+        Mosquito::new(cfg)
+    }
+
+    /// Distance to `part` alone, `INFINITY` when the settings hide it.
+    fn part_sdf(&self, part: MosquitoPart, comp: &Computation) -> f64 {
+        let x = comp.x;
+        let y = comp.y;
+        let z = comp.z;
+
+        let inner_r1 = self.cfg.given.first_needle_x - self.cfg.given.hill_xshift;
+        let inner_r =
+            self.cfg.given.first_needle_x + self.cfg.derived.outer_holder_xmax
+            - self.cfg.given.outer_thickness - self.cfg.given.holder_gap;
+        let inner_circle = || {
+            (((x + self.cfg.given.first_needle_x).powi(2) + y*y).sqrt() - inner_r)
+            .max(inner_r1 - ((x + self.cfg.given.first_needle_x).powi(2) + y*y).sqrt())
+        };
+
+        match part {
+            MosquitoPart::InnerNeedle => {
+                if self.cfg.given.show_inner_needle { inner_needle(comp) } else { f64::INFINITY }
+            }
+            MosquitoPart::OuterNeedle => {
+                if self.cfg.given.show_outer_needle { outer_needle(comp) } else { f64::INFINITY }
+            }
+            MosquitoPart::InnerHolder => {
+                if !self.cfg.given.show_inner_holder {
+                    return f64::INFINITY;
+                }
+                let mut inhold = inner_holder(comp);
+                if self.cfg.given.show_inner_hole {
+                    inhold = inhold.max(
+                        -comp.inner_needle_handle().max(plate_slabs(comp, &self.cfg.derived.inner_plates)),
+                    );
+                }
+                inhold.max(inner_circle())
+            }
+            MosquitoPart::OuterHolder => {
+                let outer_needle_z = plate_slabs(comp, &self.cfg.derived.outer_slots);
+                outer_holder(comp)
+                    .max(-comp.outer_needle_handle().max(outer_needle_z))
+                    .max(y - self.cfg.given.outer_holder_height)
+            }
+            MosquitoPart::Connector => {
+                if !self.cfg.given.show_connector {
+                    return f64::INFINITY;
+                }
+                comp.needletop_connect()
+                    .max(self.cfg.given.thickness - z)
+                    .max(z - self.cfg.given.needle_distance_z*2.0 + self.cfg.given.thickness)
+                    .max(inner_circle())
+            }
+        }
+    }
+}
+
 impl SDFSurface for Mosquito {
     fn bounding_box(&self) -> [Vector3<f32>; 2] {
         [
@@ -329,66 +583,13 @@ impl SDFSurface for Mosquito {
         ]
     }
 
+    /// One colour for every part, so `distance_only` has no work to skip.
     fn sample(&self, p: Vector3<f32>, _distance_only: bool) -> SDFSample {
-        let x = p.x as f64;
-        let y = p.y as f64;
-        let z = p.z as f64;
-        let comp = Computation::new(&self.cfg, x, y, z);
-
-        let mut outer_needle_z = INFINITY;
-        for i in 0..self.cfg.given.needle_count_z {
-            let zp = self.cfg.given.needle_distance_z * i as f64 * 2.0;
-            outer_needle_z = outer_needle_z
-                .min(
-                    (-z - self.cfg.given.steel_thickness - self.cfg.given.needle_distance_z + zp)
-                    .max(z - self.cfg.given.steel_thickness + self.cfg.given.needle_distance_z - zp)
-                    );
-        }
-
-        let inner_r1 = self.cfg.given.first_needle_x - self.cfg.given.hill_xshift;
-        let inner_r =
-            self.cfg.given.first_needle_x + self.cfg.derived.outer_holder_xmax
-            - self.cfg.given.outer_thickness - self.cfg.given.holder_gap;
-        let inner_circle =
-            (((x + self.cfg.given.first_needle_x).powi(2) + y*y).sqrt() - inner_r)
-            .max(inner_r1 - ((x + self.cfg.given.first_needle_x).powi(2) + y*y).sqrt());
-
-        let mut result = INFINITY;
-
-        if self.cfg.given.show_inner_holder {
-            let mut inhold = inner_holder(&comp);
-            if self.cfg.given.show_inner_hole {
-                inhold = inhold.max(
-                    -comp.inner_needle_handle()
-                        .max(-self.cfg.given.steel_thickness - z)
-                        .max(z - self.cfg.given.steel_thickness),
-                );
-            }
-            result = result.min(inhold);
-        }
-
-
-        if self.cfg.given.show_connector {
-            result = result.min(
-                comp.needletop_connect()
-                    .max(self.cfg.given.thickness - z)
-                    .max(z - self.cfg.given.needle_distance_z*2.0 + self.cfg.given.thickness)
-            );
-        }
-
-        result = result.max(inner_circle);
-        result = result.min(
-            outer_holder(&comp)
-                .max(-comp.outer_needle_handle().max(outer_needle_z))
-                .max(y - self.cfg.given.outer_holder_height)
-        );
-
-        if self.cfg.given.show_inner_needle {
-            result = result.min(inner_needle(&comp));
-        }
-        if self.cfg.given.show_outer_needle {
-            result = result.min(outer_needle(&comp));
-        }
+        let comp = Computation::new(&self.cfg, p.x as f64, p.y as f64, p.z as f64);
+        let result = MosquitoPart::ALL
+            .iter()
+            .map(|&part| self.part_sdf(part, &comp))
+            .fold(INFINITY, f64::min);
 
         SDFSample::new(
             result as f32,
@@ -397,89 +598,114 @@ impl SDFSurface for Mosquito {
     }
 }
 
-pub fn needle_straight(cfg: &Settings, x: f64, y: f64) -> f64 {
-    let mut d = trapezoid(x, y, cfg.given.needle_xs1[0], cfg.given.needle_xs2[0], cfg.given.needle_ys[0]);
-    let mut right = 0.5 * cfg.given.needle_xs2[0];
-    let mut left = -right;
-    for i in 1..cfg.given.needle_ys.len() {
-        let shift =
-            if i & 1 == 1 { 0.5 * cfg.given.needle_xs1[i] - right }
-            else { -0.5 * cfg.given.needle_xs1[i] - left };
-        left = -shift - 0.5 * cfg.given.needle_xs2[i];
-        right = -shift + 0.5 * cfg.given.needle_xs2[i];
-        d = d.min(trapezoid(
-            x + shift, y - cfg.given.needle_ys[i-1],
-            cfg.given.needle_xs1[i], cfg.given.needle_xs2[i],
-            cfg.given.needle_ys[i] - cfg.given.needle_ys[i-1],
-        ));
+impl Parts for Mosquito {
+    fn parts(&self) -> Vec<Part> {
+        MosquitoPart::ALL.iter().map(|part| part.part()).collect()
     }
-    d.max(cfg.given.needle_cut_r - (x*x - (y + cfg.given.needle_cut_y).powi(2)).sqrt())
-}
 
-fn inner_needles_straight(cfg: &Settings, x: f64, y: f64) -> f64 {
-    let mut d = needle_straight(cfg, x, y);
-    for i in 1..cfg.given.needle_count - 1 {
-        d = d.min(needle_straight(cfg, x - cfg.given.needle_distance * i as f64, y));
+    fn part_distance(&self, part: PartId, p: Vector3<f32>) -> f32 {
+        let comp = Computation::new(&self.cfg, p.x as f64, p.y as f64, p.z as f64);
+        self.part_sdf(MosquitoPart::ALL[part], &comp) as f32
     }
-    d
 }
 
-fn outer_needles_straight(cfg: &Settings, x: f64, y: f64) -> f64 {
-    let x2 = x + cfg.derived.needle_distance_x_diag;
-    let mut d = needle_straight(cfg, x2, y);
-    for i in 1..cfg.given.needle_count {
-        d = d.min(needle_straight(cfg, x2 - cfg.given.needle_distance * i as f64, y));
+/// Distance to a single straight needle with the first profile, in the needle's own frame.
+pub fn needle_straight(cfg: &Settings, x: f64, y: f64) -> f64 {
+    cfg.derived.needle_profiles[0].distance(x, y)
+}
+
+/// The bent teeth of `needles` projected into one plane, without the handle and plate
+/// thickness.
+fn teeth<'a>(comp: &Computation, needles: impl Iterator<Item = &'a NeedlePlacement>) -> f64 {
+    let cfg = comp.cfg;
+    let bend = |angle| circ_coordinates(comp.x + cfg.derived.inner_needle_x, comp.y, angle);
+    let bent = bend(cfg.given.penetration_angle);
+    needles
+        .map(|needle| {
+            let (xc, yc) = needle.angle.map_or(bent, bend);
+            cfg.derived.needle_profiles[needle.profile].distance(xc - cfg.derived.inner_needle_x - needle.x, yc)
+        })
+        .fold(f64::INFINITY, f64::min)
+        .max(comp.y - 2.0)
+}
+
+fn inner_teeth(comp: &Computation) -> f64 {
+    teeth(comp, comp.cfg.derived.inner_needles.iter())
+}
+
+fn outer_teeth(comp: &Computation) -> f64 {
+    teeth(comp, comp.cfg.derived.outer_needles.iter())
+}
+
+/// Distance to the steel sheets at the heights `plates`.
+fn plate_slabs(comp: &Computation, plates: &[f64]) -> f64 {
+    plates
+        .iter()
+        .map(|&z| (comp.z - z).abs() - comp.cfg.given.steel_thickness)
+        .fold(f64::INFINITY, f64::min)
+}
+
+/// The plates of `needles`, each with its teeth and `handle` cut to the steel thickness.
+fn needle_plates(comp: &Computation, needles: &[NeedlePlacement], plates: &[f64], handle: f64) -> f64 {
+    let mut d = f64::INFINITY;
+    for &z in plates {
+        let slab = (comp.z - z).abs() - comp.cfg.given.steel_thickness;
+        // Nothing on a plate is closer than its faces
+        if slab < d {
+            d = d.min(teeth(comp, needles.iter().filter(|n| n.z == z)).min(handle).max(slab));
+        }
     }
     d
 }
 
 fn inner_needle(comp: &Computation) -> f64 {
-    let (xc, yc) = circ_coordinates(
-        comp.x + comp.cfg.derived.inner_needle_x, comp.y, comp.cfg.given.penetration_angle);
-    inner_needles_straight(comp.cfg, xc - comp.cfg.derived.inner_needle_x, yc)
-        .max(comp.y - 2.0)
-        .min(
-            comp.inner_needle_handle()
-                .max(-comp.x - comp.cfg.given.hill_xshift)
-                .max(comp.x - comp.cfg.derived.inner_holder_xmax)
-        )
-        .max(-comp.cfg.given.steel_thickness - comp.z)
-        .max(comp.z - comp.cfg.given.steel_thickness)
+    let cfg = comp.cfg;
+    let handle = comp.inner_needle_handle()
+        .max(cfg.derived.inner_needle_xmin - cfg.given.hill_xshift - comp.x)
+        .max(comp.x - cfg.derived.inner_holder_xmax);
+    needle_plates(comp, &cfg.derived.inner_needles, &cfg.derived.inner_plates, handle)
 }
 
 fn outer_needle(comp: &Computation) -> f64 {
-    let (xc, yc) = circ_coordinates(
-        comp.x + comp.cfg.derived.inner_needle_x, comp.y, comp.cfg.given.penetration_angle);
-    outer_needles_straight(comp.cfg, xc - comp.cfg.derived.inner_needle_x, yc)
-        .max(comp.y - 2.0)
-        .min(
-            comp.outer_needle_handle()
-                .max(-comp.x + comp.cfg.derived.outer_holder_xmin)
-                .max(comp.x - comp.cfg.derived.outer_holder_xmax)
-        )
-        .max(-comp.cfg.given.steel_thickness - comp.cfg.given.needle_distance_z - comp.z)
-        .max(comp.z - comp.cfg.given.steel_thickness + comp.cfg.given.needle_distance_z)
+    let cfg = comp.cfg;
+    let handle = comp.outer_needle_handle()
+        .max(-comp.x + cfg.derived.outer_holder_xmin)
+        .max(comp.x - cfg.derived.outer_holder_xmax);
+    needle_plates(comp, &cfg.derived.outer_needles, &cfg.derived.outer_plates, handle)
 }
 
+/// All needle plates, whatever the `show_*` settings.
 #[derive(Debug, Clone, PartialEq)]
 pub struct Needle {
     cfg: Settings,
 }
 
+impl Needle {
+    pub fn new(cfg: Settings) -> Self {
+        Self { cfg }
+    }
+}
+
+/// Box around the needles' x and y extent and the steel of `plates`.
+fn needle_box<'a>(cfg: &Settings, plates: impl Iterator<Item = &'a f64>) -> [Vector3<f32>; 2] {
+    let (zmin, zmax) = plates.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &z| (lo.min(z), hi.max(z)));
+    [
+        Vector3::new(
+            -30.0 + cfg.derived.outer_needle_xmin.min(cfg.derived.inner_needle_xmin) as f32,
+            -120.0,
+            (zmin - cfg.given.steel_thickness) as f32,
+        ),
+        Vector3::new(
+            cfg.derived.outer_holder_xmax as f32 + 10.0,
+            (cfg.given.hill_height + cfg.given.outer_needletop_width) as f32,
+            (zmax + cfg.given.steel_thickness) as f32,
+        ),
+    ]
+}
+
 impl SDFSurface for Needle {
     fn bounding_box(&self) -> [Vector3<f32>; 2] {
-        [
-            Vector3::new(
-                -30.0 - self.cfg.derived.needle_distance_x_diag as f32,
-                -120.0,
-                (-self.cfg.given.steel_thickness - self.cfg.given.needle_distance_z) as f32,
-            ),
-            Vector3::new(
-                self.cfg.derived.outer_holder_xmax as f32 + 10.0,
-                (self.cfg.given.hill_height + self.cfg.given.outer_needletop_width) as f32,
-                self.cfg.given.steel_thickness as f32,
-            ),
-        ]
+        needle_box(&self.cfg, self.cfg.derived.inner_plates.iter().chain(&self.cfg.derived.outer_plates))
     }
 
     fn sample(&self, p: Vector3<f32>, _distance_only: bool) -> SDFSample {
@@ -497,11 +723,51 @@ impl SDFSurface for Needle {
     }
 }
 
+/// The inner needle plates on their own, whatever the `show_*` settings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InnerNeedle {
+    cfg: Settings,
+}
+
+impl InnerNeedle {
+    pub fn new(cfg: Settings) -> Self {
+        Self { cfg }
+    }
+}
+
+impl SDFSurface for InnerNeedle {
+    fn bounding_box(&self) -> [Vector3<f32>; 2] {
+        needle_box(&self.cfg, self.cfg.derived.inner_plates.iter())
+    }
+
+    fn sample(&self, p: Vector3<f32>, _distance_only: bool) -> SDFSample {
+        let comp = Computation::new(&self.cfg, p.x as f64, p.y as f64, p.z as f64);
+        SDFSample::new(inner_needle(&comp) as f32, MosquitoPart::InnerNeedle.color())
+    }
+}
+
+/// The outer needle plates on their own, whatever the `show_*` settings.
 #[derive(Debug, Clone, PartialEq)]
 pub struct OuterNeedle {
     cfg: Settings,
 }
 
+impl OuterNeedle {
+    pub fn new(cfg: Settings) -> Self {
+        Self { cfg }
+    }
+}
+
+impl SDFSurface for OuterNeedle {
+    fn bounding_box(&self) -> [Vector3<f32>; 2] {
+        needle_box(&self.cfg, self.cfg.derived.outer_plates.iter())
+    }
+
+    fn sample(&self, p: Vector3<f32>, _distance_only: bool) -> SDFSample {
+        let comp = Computation::new(&self.cfg, p.x as f64, p.y as f64, p.z as f64);
+        SDFSample::new(outer_needle(&comp) as f32, MosquitoPart::OuterNeedle.color())
+    }
+}
 
 /// Circular coordinate transformation
 pub fn circ_coordinates(x: f64, y: f64, a: f64) -> (f64, f64) {
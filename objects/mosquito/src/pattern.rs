@@ -0,0 +1,47 @@
+//! Flat patterns of the steel needle plates for laser or waterjet cutting.
+//!
+//! Each needle is a `steel_thickness` sheet on both sides of its mid-plane, so its flat
+//! pattern is the zero contour of the part in that plane, in mm.
+
+use press_common::outline::{Outline, OutlineConfig};
+use press_common::part::PartSurface;
+use press_common::slice::{Axis, SlicePlane};
+
+use crate::{Mosquito, MosquitoPart, Needle, SDFSurface};
+
+/// Margin around the needle bounding box when tracing, in mm.
+const MARGIN: f32 = 2.0;
+
+impl MosquitoPart {
+    /// The parts cut from sheet steel.
+    pub const PLATES: [MosquitoPart; 2] = [MosquitoPart::InnerNeedle, MosquitoPart::OuterNeedle];
+}
+
+impl Mosquito {
+    /// Heights of the mid-planes of a part's needle plates, empty for the printed parts.
+    pub fn plates(&self, part: MosquitoPart) -> &[f64] {
+        match part {
+            MosquitoPart::InnerNeedle => &self.cfg.derived.inner_plates,
+            MosquitoPart::OuterNeedle => &self.cfg.derived.outer_plates,
+            _ => &[],
+        }
+    }
+
+    /// Flat outline of the needle plate of `part` with its mid-plane at `z`, whether or not
+    /// the settings show it.
+    ///
+    /// `None` for the printed parts.
+    pub fn flat_pattern(&self, part: MosquitoPart, z: f64, cfg: &OutlineConfig) -> Option<Outline> {
+        if !MosquitoPart::PLATES.contains(&part) {
+            return None;
+        }
+        let [lo, hi] = Needle::new(self.cfg.clone()).bounding_box();
+        Some(Outline::trace(
+            &PartSurface::new(&self.assembly(), part.id()),
+            SlicePlane::axis(Axis::Z, z as f32),
+            [lo.x - MARGIN, lo.y - MARGIN],
+            [hi.x + MARGIN, hi.y + MARGIN],
+            cfg,
+        ))
+    }
+}
@@ -0,0 +1,278 @@
+//! Needle tooth profiles as closed outlines of line and quadratic Bézier segments.
+//!
+//! The profile is drawn in the straight needle's frame: x across the needle, y along it from
+//! the tip at y = 0 towards the handle. Its distance is exact, the unsigned distance to the
+//! nearest segment signed by a crossing count, so barbs can be curved or asymmetric.
+
+/// A segment of a [`Profile`], starting where the previous one ends.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Segment {
+    Line { to: [f64; 2] },
+    /// Quadratic Bézier curve bending towards `control`.
+    Quadratic { control: [f64; 2], to: [f64; 2] },
+}
+
+impl Segment {
+    pub fn to(self) -> [f64; 2] {
+        match self {
+            Segment::Line { to } | Segment::Quadratic { to, .. } => to,
+        }
+    }
+}
+
+/// A closed outline, the last segment is joined back to `start` by a line if it ends elsewhere.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Profile {
+    pub start: [f64; 2],
+    pub segments: Vec<Segment>,
+}
+
+impl Profile {
+    /// A polygon through `points` in order, which must not be empty.
+    pub fn polygon(points: &[[f64; 2]]) -> Self {
+        assert!(!points.is_empty(), "a polygon needs at least one point");
+        Self {
+            start: points[0],
+            segments: points[1..].iter().map(|&to| Segment::Line { to }).collect(),
+        }
+    }
+
+    /// The outline of stacked trapezoids, the original tooth generator.
+    ///
+    /// Trapezoid `i` spans `ys[i - 1]..ys[i]` (from 0 for the first) with widths `xs1[i]` at
+    /// the bottom and `xs2[i]` at the top. Each trapezoid is shifted to continue the previous
+    /// one's right edge when `i` is odd and its left edge when even, so the steps on the other
+    /// side form the barbs. With `cut_r > 0` the corners of the tip are chamfered along
+    /// `|x| = y + cut_y`.
+    pub fn trapezoid_stack(ys: &[f64], xs1: &[f64], xs2: &[f64], cut_y: f64, cut_r: f64) -> Self {
+        let mut right = vec![[0.5 * xs1[0], 0.0], [0.5 * xs2[0], ys[0]]];
+        let mut left = vec![[-0.5 * xs1[0], 0.0], [-0.5 * xs2[0], ys[0]]];
+        for i in 1..ys.len() {
+            let (r, l) = (right.last().unwrap()[0], left.last().unwrap()[0]);
+            let centre = if i & 1 == 1 { r - 0.5 * xs1[i] } else { l + 0.5 * xs1[i] };
+            right.extend([[centre + 0.5 * xs1[i], ys[i - 1]], [centre + 0.5 * xs2[i], ys[i]]]);
+            left.extend([[centre - 0.5 * xs1[i], ys[i - 1]], [centre - 0.5 * xs2[i], ys[i]]]);
+        }
+
+        // Where the chamfer meets the first trapezoid's sides
+        let slope = 0.5 * (xs2[0] - xs1[0]) / ys[0];
+        let y_cut = (0.5 * xs1[0] - cut_y) / (1.0 - slope);
+        if cut_r > 0.0 && (0.0..ys[0]).contains(&y_cut) {
+            let x_cut = y_cut + cut_y;
+            right.splice(0..1, [[cut_y, 0.0], [x_cut, y_cut]]);
+            left.splice(0..1, [[-cut_y, 0.0], [-x_cut, y_cut]]);
+        }
+
+        let mut points = right;
+        points.extend(left.into_iter().rev());
+        points.dedup_by(|a, b| (a[0] - b[0]).abs() < 1e-9 && (a[1] - b[1]).abs() < 1e-9);
+        Self::polygon(&points)
+    }
+
+    /// Segments as `(from, segment)` pairs, including the closing line.
+    fn edges(&self) -> impl Iterator<Item = ([f64; 2], Segment)> + '_ {
+        let last = self.segments.last().map_or(self.start, |s| s.to());
+        let closing = (last != self.start).then_some(Segment::Line { to: self.start });
+        let froms = std::iter::once(self.start).chain(self.segments.iter().map(|s| s.to()));
+        froms.zip(self.segments.iter().copied().chain(closing))
+    }
+
+    /// Signed distance to the outline, negative inside.
+    pub fn distance(&self, x: f64, y: f64) -> f64 {
+        let p = [x, y];
+        let mut d2 = f64::INFINITY;
+        let mut inside = false;
+        for (from, segment) in self.edges() {
+            match segment {
+                Segment::Line { to } => {
+                    d2 = d2.min(line_distance2(p, from, to));
+                    inside ^= line_crosses(p, from, to);
+                }
+                Segment::Quadratic { control, to } => {
+                    d2 = d2.min(quadratic_distance2(p, from, control, to));
+                    inside ^= quadratic_crosses(p, from, control, to);
+                }
+            }
+        }
+        if inside { -d2.sqrt() } else { d2.sqrt() }
+    }
+
+    /// `[min, max]` of the end and control points, which contain the outline.
+    pub fn bounds(&self) -> [[f64; 2]; 2] {
+        let mut lo = self.start;
+        let mut hi = self.start;
+        for segment in &self.segments {
+            let points = match *segment {
+                Segment::Line { to } => vec![to],
+                Segment::Quadratic { control, to } => vec![control, to],
+            };
+            for q in points {
+                lo = [lo[0].min(q[0]), lo[1].min(q[1])];
+                hi = [hi[0].max(q[0]), hi[1].max(q[1])];
+            }
+        }
+        [lo, hi]
+    }
+
+    /// Width of the outline at its top end, where the needle joins the handle.
+    pub fn top_width(&self) -> f64 {
+        let top = self.bounds()[1][1];
+        let xs = self.edges().map(|(from, _)| from).filter(|q| (q[1] - top).abs() < 1e-9).map(|q| q[0]);
+        let (lo, hi) = xs.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), x| (lo.min(x), hi.max(x)));
+        hi - lo
+    }
+}
+
+fn sub(a: [f64; 2], b: [f64; 2]) -> [f64; 2] {
+    [a[0] - b[0], a[1] - b[1]]
+}
+
+fn dot(a: [f64; 2], b: [f64; 2]) -> f64 {
+    a[0] * b[0] + a[1] * b[1]
+}
+
+fn line_distance2(p: [f64; 2], a: [f64; 2], b: [f64; 2]) -> f64 {
+    let (pa, ba) = (sub(p, a), sub(b, a));
+    let len2 = dot(ba, ba);
+    let t = if len2 > 0.0 { (dot(pa, ba) / len2).clamp(0.0, 1.0) } else { 0.0 };
+    let e = [pa[0] - ba[0] * t, pa[1] - ba[1] * t];
+    dot(e, e)
+}
+
+/// Whether the ray from `p` towards +x crosses the line `a`-`b`, with half-open end points so
+/// that a vertex shared by two segments is counted once.
+fn line_crosses(p: [f64; 2], a: [f64; 2], b: [f64; 2]) -> bool {
+    (p[1] >= a[1]) != (p[1] >= b[1]) && a[0] + (p[1] - a[1]) / (b[1] - a[1]) * (b[0] - a[0]) > p[0]
+}
+
+fn quadratic_point(a: [f64; 2], c: [f64; 2], b: [f64; 2], t: f64) -> [f64; 2] {
+    let s = 1.0 - t;
+    [s * s * a[0] + 2.0 * s * t * c[0] + t * t * b[0], s * s * a[1] + 2.0 * s * t * c[1] + t * t * b[1]]
+}
+
+/// Squared distance to the quadratic Bézier `a`, `c`, `b`: the closest point is a root of a
+/// cubic, solved in closed form.
+fn quadratic_distance2(p: [f64; 2], a: [f64; 2], c: [f64; 2], b: [f64; 2]) -> f64 {
+    let ca = sub(c, a);
+    let k = [a[0] - 2.0 * c[0] + b[0], a[1] - 2.0 * c[1] + b[1]];
+    let kk = dot(k, k);
+    if kk < 1e-18 {
+        return line_distance2(p, a, b);
+    }
+    let d = sub(a, p);
+    let kx = dot(ca, k) / kk;
+    let ky = (2.0 * dot(ca, ca) + dot(d, k)) / (3.0 * kk);
+    let kz = dot(d, ca) / kk;
+    let q2 = ky - kx * kx;
+    let q3 = q2 * q2 * q2;
+    let r = kx * (2.0 * kx * kx - 3.0 * ky) + kz;
+    let h = r * r + 4.0 * q3;
+    let ts = if h >= 0.0 {
+        let h = h.sqrt();
+        vec![((h - r) / 2.0).cbrt() + ((-h - r) / 2.0).cbrt() - kx]
+    } else {
+        let z = (-q2).sqrt();
+        let v = (r / (q2 * z * 2.0)).clamp(-1.0, 1.0).acos() / 3.0;
+        let (m, n) = (v.cos(), v.sin() * 3f64.sqrt());
+        vec![(m + m) * z - kx, (-n - m) * z - kx]
+    };
+    ts.into_iter()
+        .map(|t| {
+            let e = sub(quadratic_point(a, c, b, t.clamp(0.0, 1.0)), p);
+            dot(e, e)
+        })
+        .fold(f64::INFINITY, f64::min)
+}
+
+/// Crossings of the ray from `p` towards +x with the quadratic Bézier `a`, `c`, `b`, the curve
+/// split where it turns in y so that every piece follows the half-open rule of [`line_crosses`].
+fn quadratic_crosses(p: [f64; 2], a: [f64; 2], c: [f64; 2], b: [f64; 2]) -> bool {
+    // y(t) = qa t^2 + qb t + a.y
+    let qa = a[1] - 2.0 * c[1] + b[1];
+    let qb = 2.0 * (c[1] - a[1]);
+    let turn = if qa != 0.0 { -qb / (2.0 * qa) } else { -1.0 };
+    let splits = if turn > 0.0 && turn < 1.0 { vec![0.0, turn, 1.0] } else { vec![0.0, 1.0] };
+    let mut crosses = false;
+    for w in splits.windows(2) {
+        let (y0, y1) = (quadratic_point(a, c, b, w[0])[1], quadratic_point(a, c, b, w[1])[1]);
+        if (p[1] >= y0) == (p[1] >= y1) {
+            continue;
+        }
+        // The root of y(t) = p.y within the monotone piece
+        let qc = a[1] - p[1];
+        let t = if qa.abs() < 1e-12 {
+            -qc / qb
+        } else {
+            let s = (qb * qb - 4.0 * qa * qc).max(0.0).sqrt();
+            let (t1, t2) = ((-qb - s) / (2.0 * qa), (-qb + s) / (2.0 * qa));
+            let mid = 0.5 * (w[0] + w[1]);
+            if (t1 - mid).abs() < (t2 - mid).abs() { t1 } else { t2 }
+        };
+        crosses ^= quadratic_point(a, c, b, t.clamp(w[0], w[1]))[0] > p[0];
+    }
+    crosses
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A tooth with a convex right flank and a barb on the left whose y turns at t = 2/7.
+    fn curved_tooth() -> Profile {
+        Profile {
+            start: [-1.0, 0.0],
+            segments: vec![
+                Segment::Line { to: [1.0, 0.0] },
+                Segment::Quadratic { control: [2.0, 3.0], to: [0.0, 5.0] },
+                Segment::Quadratic { control: [-3.0, 7.0], to: [-1.0, 2.0] },
+            ],
+        }
+    }
+
+    /// The same outline with each curve replaced by `n` lines.
+    fn polyline(profile: &Profile, n: usize) -> Profile {
+        let mut points = vec![profile.start];
+        let mut from = profile.start;
+        for &segment in &profile.segments {
+            match segment {
+                Segment::Line { to } => points.push(to),
+                Segment::Quadratic { control, to } => {
+                    points.extend((1..=n).map(|i| quadratic_point(from, control, to, i as f64 / n as f64)))
+                }
+            }
+            from = segment.to();
+        }
+        Profile::polygon(&points)
+    }
+
+    #[test]
+    fn quadratic_matches_dense_polyline() {
+        let (tooth, reference) = (curved_tooth(), polyline(&curved_tooth(), 1000));
+        for i in 0..=40 {
+            for j in 0..=50 {
+                let (x, y) = (-4.0 + 0.2 * i as f64 + 0.013, -2.0 + 0.2 * j as f64 + 0.007);
+                let (d, expected) = (tooth.distance(x, y), reference.distance(x, y));
+                assert!((d - expected).abs() < 1e-4, "at ({}, {}): {} != {}", x, y, d, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn crossings_split_where_the_curve_turns() {
+        // At y = 5.3 the barb is crossed twice, at x = -1.73 and x = -0.49
+        let tooth = curved_tooth();
+        assert!(tooth.distance(-3.0, 5.3) > 0.0);
+        assert!(tooth.distance(-1.0, 5.3) < 0.0);
+        assert!(tooth.distance(0.0, 5.3) > 0.0);
+        // Level with the top of the turn and with the curve's end points
+        assert!(tooth.distance(-3.0, 5.0 + 4.0 / 7.0) > 0.0);
+        assert!(tooth.distance(-0.5, 5.0) < 0.0);
+        assert!(tooth.distance(-0.5, 2.0) < 0.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one point")]
+    fn empty_polygon_panics() {
+        Profile::polygon(&[]);
+    }
+}
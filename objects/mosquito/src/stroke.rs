@@ -0,0 +1,287 @@
+//! Kinematics of the press stroke.
+//!
+//! The inner assembly (inner needle, inner holder and connector) turns around the pivot at
+//! `x = -first_needle_x`, `y = 0` about the z axis while the outer needle and holder stay put.
+//! The needles are plates in parallel planes that pass each other like shear blades, so teeth
+//! overlap is measured on the teeth projected into one plane. Collisions of the holders are
+//! measured in 3D, both with `press_common::clearance`.
+
+use std::fmt;
+
+use press_common::clearance::{clearances, Clearance, ClearanceConfig};
+use press_common::slice::{write_surface_strip_png, Axis, Slice, SlicePlane};
+
+use crate::{
+    inner_teeth, outer_teeth, Computation, Mosquito, MosquitoPart, Part, PartId, Parts, SDFSample, SDFSurface,
+    Vector3,
+};
+
+impl MosquitoPart {
+    /// The parts moved by the stroke.
+    pub const INNER_ASSEMBLY: [MosquitoPart; 3] =
+        [MosquitoPart::InnerNeedle, MosquitoPart::InnerHolder, MosquitoPart::Connector];
+
+    pub fn moves_with_stroke(self) -> bool {
+        MosquitoPart::INNER_ASSEMBLY.contains(&self)
+    }
+}
+
+/// Parameters of [`Mosquito::stroke`].
+#[derive(Debug, Clone)]
+pub struct StrokeConfig {
+    /// Rotation of the inner assembly at the end of the stroke in radians, counter-clockwise
+    /// seen from +z. Defaults to the default `penetration_angle`, the other way the connector
+    /// runs into the outer holder.
+    pub angle: f64,
+    /// Steps after the start position, 0 for the end position alone.
+    pub steps: usize,
+    /// Search parameters, `min_clearance` is the smallest allowed gap of a holder.
+    pub clearance: ClearanceConfig,
+}
+
+impl Default for StrokeConfig {
+    fn default() -> Self {
+        Self {
+            angle: 0.35,
+            steps: 12,
+            clearance: ClearanceConfig { seeds: 32, ..Default::default() },
+        }
+    }
+}
+
+impl StrokeConfig {
+    /// Rotation at each step, from 0 to `angle`.
+    pub fn angles(&self) -> Vec<f64> {
+        if self.steps == 0 {
+            return vec![self.angle];
+        }
+        (0..=self.steps).map(|i| self.angle * i as f64 / self.steps as f64).collect()
+    }
+}
+
+/// The press at one position of the stroke.
+#[derive(Debug, Clone)]
+pub struct StrokePose {
+    mosquito: Mosquito,
+    angle: f64,
+}
+
+impl StrokePose {
+    /// Where a point of the world is in the frame of the inner assembly.
+    pub fn to_inner(&self, p: Vector3<f32>) -> Vector3<f32> {
+        rotate(p, -self.angle, self.mosquito.cfg.given.first_needle_x)
+    }
+
+    /// Teeth of the inner needle (part 0) and the outer needle (part 1) in one plane.
+    pub fn teeth(&self) -> Teeth {
+        Teeth { pose: self.clone() }
+    }
+}
+
+impl SDFSurface for StrokePose {
+    fn bounding_box(&self) -> [Vector3<f32>; 2] {
+        // The fixed parts' box and the moved corners of the inner assembly's
+        let [mut lo, mut hi] = self.mosquito.bounding_box();
+        for corner in [(lo.x, lo.y), (hi.x, lo.y), (lo.x, hi.y), (hi.x, hi.y)] {
+            let p = rotate(Vector3::new(corner.0, corner.1, 0.0), self.angle, self.mosquito.cfg.given.first_needle_x);
+            lo = Vector3::new(lo.x.min(p.x), lo.y.min(p.y), lo.z);
+            hi = Vector3::new(hi.x.max(p.x), hi.y.max(p.y), hi.z);
+        }
+        [lo, hi]
+    }
+
+    fn sample(&self, p: Vector3<f32>, _distance_only: bool) -> SDFSample {
+        let (part, distance) = self
+            .part_distances(p)
+            .into_iter()
+            .enumerate()
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .unwrap();
+        SDFSample::new(distance, MosquitoPart::ALL[part].color())
+    }
+}
+
+impl Parts for StrokePose {
+    fn parts(&self) -> Vec<Part> {
+        self.mosquito.parts()
+    }
+
+    fn part_distance(&self, part: PartId, p: Vector3<f32>) -> f32 {
+        let p = if MosquitoPart::ALL[part].moves_with_stroke() { self.to_inner(p) } else { p };
+        self.mosquito.part_distance(part, p)
+    }
+}
+
+/// The teeth of both needles at one position of the stroke, moved into the plane `z = 0`.
+#[derive(Debug, Clone)]
+pub struct Teeth {
+    pose: StrokePose,
+}
+
+impl SDFSurface for Teeth {
+    fn bounding_box(&self) -> [Vector3<f32>; 2] {
+        let [lo, hi] = self.pose.bounding_box();
+        [Vector3::new(lo.x, lo.y, 0.0), Vector3::new(hi.x, hi.y, 0.0)]
+    }
+
+    fn sample(&self, p: Vector3<f32>, _distance_only: bool) -> SDFSample {
+        let [inner, outer] = [0, 1].map(|part| self.part_distance(part, p));
+        let part = if inner <= outer { MosquitoPart::InnerNeedle } else { MosquitoPart::OuterNeedle };
+        SDFSample::new(inner.min(outer), part.color())
+    }
+}
+
+impl Parts for Teeth {
+    fn parts(&self) -> Vec<Part> {
+        [MosquitoPart::InnerNeedle, MosquitoPart::OuterNeedle].map(|part| part.part()).to_vec()
+    }
+
+    fn part_distance(&self, part: PartId, p: Vector3<f32>) -> f32 {
+        let cfg = &self.pose.mosquito.cfg;
+        if part == 0 {
+            let q = self.pose.to_inner(p);
+            inner_teeth(&Computation::new(cfg, q.x as f64, q.y as f64, 0.0)) as f32
+        } else {
+            outer_teeth(&Computation::new(cfg, p.x as f64, p.y as f64, -cfg.given.needle_distance_z)) as f32
+        }
+    }
+}
+
+/// Teeth and holders at one position of the stroke.
+#[derive(Debug, Clone)]
+pub struct StrokeStep {
+    pub angle: f64,
+    /// Closest approach of the inner and outer teeth in projection, negative when they
+    /// overlap. `None` when either needle has no teeth.
+    pub teeth: Option<Clearance>,
+    /// Closest moving and fixed parts in 3D, `None` when all moving or all fixed parts are
+    /// empty.
+    pub holders: Option<Clearance>,
+}
+
+#[derive(Debug, Clone)]
+pub struct StrokeReport {
+    pub steps: Vec<StrokeStep>,
+    /// Part names indexed by part ID.
+    pub names: Vec<String>,
+    pub min_clearance: f32,
+}
+
+impl StrokeReport {
+    /// The first step at which the teeth overlap.
+    pub fn first_overlap(&self) -> Option<&StrokeStep> {
+        self.steps.iter().find(|step| step.teeth.as_ref().is_some_and(|teeth| teeth.separation < 0.0))
+    }
+
+    /// Deepest overlap of the teeth over the stroke, 0 when they never meet.
+    pub fn penetration(&self) -> f32 {
+        self.steps.iter().flat_map(|step| &step.teeth).map(|teeth| -teeth.separation).fold(0.0, f32::max)
+    }
+
+    /// The first step at which a moving part comes closer than `min_clearance` to a fixed one.
+    pub fn first_collision(&self) -> Option<&StrokeStep> {
+        let collides = |holders: &Clearance| holders.separation < self.min_clearance;
+        self.steps.iter().find(|step| step.holders.as_ref().is_some_and(collides))
+    }
+}
+
+impl fmt::Display for StrokeReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{:>8} {:>10} {:>10}  closest parts", "angle", "teeth", "parts")?;
+        let separation = |c: &Option<Clearance>| c.as_ref().map_or("-".to_string(), |c| format!("{:.3}", c.separation));
+        for step in &self.steps {
+            write!(f, "{:>8.4} {:>10} {:>10}  ", step.angle, separation(&step.teeth), separation(&step.holders))?;
+            match &step.holders {
+                Some(holders) => writeln!(
+                    f,
+                    "{} / {}{}",
+                    self.names[holders.a],
+                    self.names[holders.b],
+                    if holders.separation < self.min_clearance { " COLLISION" } else { "" }
+                )?,
+                None => writeln!(f, "-")?,
+            }
+        }
+        match self.first_overlap() {
+            Some(step @ StrokeStep { teeth: Some(teeth), .. }) => writeln!(
+                f,
+                "teeth overlap from angle {:.4} at ({:.2}, {:.2}), up to {:.3} deep",
+                step.angle,
+                teeth.point.x,
+                teeth.point.y,
+                self.penetration()
+            )?,
+            _ if self.steps.iter().all(|step| step.teeth.is_none()) => writeln!(f, "no teeth")?,
+            _ => writeln!(f, "teeth do not overlap")?,
+        }
+        match self.first_collision() {
+            Some(step @ StrokeStep { holders: Some(holders), .. }) => writeln!(
+                f,
+                "{} and {} collide from angle {:.4}",
+                self.names[holders.a], self.names[holders.b], step.angle
+            ),
+            _ if self.steps.iter().all(|step| step.holders.is_none()) => writeln!(f, "no moving parts"),
+            _ => writeln!(f, "no collision"),
+        }
+    }
+}
+
+impl Mosquito {
+    /// The assembly with the inner assembly turned by `angle` radians.
+    pub fn stroke_pose(&self, angle: f64) -> StrokePose {
+        StrokePose { mosquito: self.assembly(), angle }
+    }
+
+    /// Teeth overlap and holder collisions at each step of the stroke.
+    pub fn stroke(&self, cfg: &StrokeConfig) -> StrokeReport {
+        let ids = |moving: bool| {
+            MosquitoPart::ALL.iter().filter(move |part| part.moves_with_stroke() == moving).map(|part| part.id())
+        };
+        let mut holders_cfg = cfg.clearance.clone();
+        for moving in [true, false] {
+            for a in ids(moving) {
+                holders_cfg.ignore.extend(ids(moving).filter(|&b| b > a).map(|b| (a, b)));
+            }
+        }
+
+        let steps = cfg
+            .angles()
+            .into_iter()
+            .map(|angle| {
+                let pose = self.stroke_pose(angle);
+                // Empty parts are skipped, leaving no pair
+                let teeth = clearances(&pose.teeth(), &cfg.clearance).pairs.into_iter().next();
+                let holders = clearances(&pose, &holders_cfg).pairs.into_iter().next();
+                StrokeStep { angle, teeth, holders }
+            })
+            .collect();
+        StrokeReport {
+            steps,
+            names: self.parts().into_iter().map(|part| part.name).collect(),
+            min_clearance: cfg.clearance.min_clearance,
+        }
+    }
+
+    /// PNG strip of the teeth plane at each step of the stroke, sampled every `step` mm.
+    pub fn write_stroke_png(&self, cfg: &StrokeConfig, step: f32, path: &str) -> std::io::Result<()> {
+        let teeth: Vec<Teeth> = cfg.angles().into_iter().map(|angle| self.stroke_pose(angle).teeth()).collect();
+        // One region for all frames
+        let (mut min, mut max) = ([f32::INFINITY; 2], [f32::NEG_INFINITY; 2]);
+        for t in &teeth {
+            let [lo, hi] = t.bounding_box();
+            min = [min[0].min(lo.x), min[1].min(lo.y)];
+            max = [max[0].max(hi.x), max[1].max(hi.y)];
+        }
+        let plane = SlicePlane::axis(Axis::Z, 0.0);
+        let frames: Vec<(Slice, &Teeth)> =
+            teeth.iter().map(|t| (Slice::sample_region(t, plane, min, max, step), t)).collect();
+        write_surface_strip_png(path, &frames)
+    }
+}
+
+/// `p` turned by `angle` about the z axis through `(-pivot_x, 0)`.
+fn rotate(p: Vector3<f32>, angle: f64, pivot_x: f64) -> Vector3<f32> {
+    let (s, c) = (angle as f32).sin_cos();
+    let x = p.x + pivot_x as f32;
+    Vector3::new(c * x - s * p.y - pivot_x as f32, s * x + c * p.y, p.z)
+}
@@ -0,0 +1,13 @@
+// Clearance checks between the parts, see press_common::clearance
+
+use press_common::clearance::ClearanceConfig;
+use press_mosquito::{Mosquito, Settings};
+
+#[test]
+fn mosquito_part_clearance() {
+    // The holders are `holder_gap` apart, leave room for print tolerances
+    let cfg = ClearanceConfig { min_clearance: 1.0, seeds: 32, ..Default::default() };
+    let report = Mosquito::new(Settings::default()).clearances(&cfg);
+    assert_eq!(report.pairs.len(), 7, "{}", report);
+    assert!(report.is_ok(), "{}", report);
+}
@@ -0,0 +1,46 @@
+// Golden-field regression tests, update with PRESS_UPDATE_GOLDEN=1 cargo test -p press-mosquito
+
+use press_common::golden::{assert_batch, assert_distance_only, assert_golden_field, assert_golden_slice};
+use press_common::slice::{Axis, SlicePlane};
+use press_mosquito::{Mosquito, Needle, Settings};
+
+const TOLERANCE: f32 = 1e-3;
+
+fn snapshot(name: &str) -> String {
+    format!("{}/tests/golden/{}.txt", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+#[test]
+fn mosquito_field() {
+    assert_golden_field(&Mosquito::new(Settings::default()), snapshot("mosquito"), 24, TOLERANCE);
+}
+
+#[test]
+fn needle_field() {
+    assert_golden_field(&Needle::new(Settings::default()), snapshot("needle"), 24, TOLERANCE);
+}
+
+#[test]
+fn inner_needle_slice() {
+    assert_golden_slice(
+        &Mosquito::new(Settings::default()),
+        SlicePlane::axis(Axis::Z, 0.0),
+        2.0,
+        snapshot("inner_needle_slice"),
+        TOLERANCE,
+    );
+}
+
+#[test]
+fn mosquito_distance_only() {
+    let mosquito = Mosquito::new(Settings::default());
+    assert_distance_only(&mosquito, 12);
+    assert_distance_only(&mosquito.assembly(), 12);
+    assert_distance_only(&mosquito.stroke_pose(0.2), 12);
+}
+
+#[test]
+fn mosquito_batch() {
+    assert_batch(&Mosquito::new(Settings::default()).assembly(), 12);
+    assert_batch(&Needle::new(Settings::default()), 12);
+}
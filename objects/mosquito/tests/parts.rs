@@ -0,0 +1,38 @@
+// Build-time part selector and standalone needles, see press_mosquito::root_sdf
+
+use press_common::golden::Field;
+use press_common::part::PartSurface;
+use press_mosquito::{root_sdf, InnerNeedle, Mosquito, MosquitoPart, OuterNeedle, SDFSurface, Settings};
+
+#[test]
+fn mosquito_part_names_resolve() {
+    let names = ["", "mosquito", "needle", "inner_needle", "outer_needle", "inner_holder", "outer_holder", "connector"];
+    for name in names {
+        let surface = root_sdf(Settings::default(), Some(name));
+        let [lo, hi] = surface.bounding_box();
+        assert!(lo.x < hi.x && lo.y < hi.y && lo.z < hi.z, "{:?}", name);
+        assert!(surface.sample((lo + hi) / 2.0, true).distance.is_finite(), "{:?}", name);
+    }
+}
+
+#[test]
+#[should_panic(expected = "unknown PRESS_PART")]
+fn mosquito_unknown_part_name_panics() {
+    root_sdf(Settings::default(), Some("holder"));
+}
+
+#[test]
+fn mosquito_needles_match_assembly_parts() {
+    let assembly = Mosquito::new(Settings::default()).assembly();
+    let needles: [(MosquitoPart, Box<dyn SDFSurface>); 2] = [
+        (MosquitoPart::InnerNeedle, Box::new(InnerNeedle::new(Settings::default()))),
+        (MosquitoPart::OuterNeedle, Box::new(OuterNeedle::new(Settings::default()))),
+    ];
+    for (part, needle) in needles {
+        let expected = PartSurface::new(&assembly, part.id());
+        for p in Field::grid(&assembly, 16).points {
+            let (a, b) = (needle.sample(p, true).distance, expected.sample(p, true).distance);
+            assert_eq!(a.to_bits(), b.to_bits(), "{:?} at {:?}: {} != {}", part, p, a, b);
+        }
+    }
+}
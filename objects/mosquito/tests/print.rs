@@ -0,0 +1,27 @@
+// Print orientation of the connector, see press_common::print
+
+use press_common::bounds::{AutoBounds, BoundsConfig};
+use press_common::mesh::MeshConfig;
+use press_common::part::PartSurface;
+use press_common::print::{analyze_print, Oriented, PrintConfig};
+use press_mosquito::{Mosquito, MosquitoPart, SDFSurface, Settings, Vector3};
+
+#[test]
+fn connector_prints_flat() {
+    let assembly = Mosquito::new(Settings::default()).assembly();
+    let surface = AutoBounds::new(
+        PartSurface::new(&assembly, MosquitoPart::Connector.id()),
+        &BoundsConfig::default(),
+        1.0,
+    );
+    let cfg = PrintConfig { mesh: MeshConfig { resolution: 64 }, ..Default::default() };
+    let report = analyze_print(&surface, &cfg);
+    let best = *report.best();
+    assert!(best.down.z.abs() > 0.999, "{}", report);
+    assert!(report.is_support_free(cfg.overhang_tolerance), "{}", report);
+
+    // The flat side lies on the bed
+    let oriented = Oriented::new(surface, &best);
+    assert!(oriented.sample(Vector3::new(0.0, 0.0, 0.5), true).distance < 0.0);
+    assert!(oriented.sample(Vector3::new(0.0, 0.0, -0.5), true).distance > 0.0);
+}
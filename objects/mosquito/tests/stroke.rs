@@ -0,0 +1,69 @@
+// Press stroke kinematics, see press_mosquito::stroke
+
+use press_mosquito::stroke::StrokeConfig;
+use press_mosquito::{Mosquito, MosquitoPart, NeedlePlacement, Parts, Settings, Vector3};
+
+fn cfg() -> StrokeConfig {
+    StrokeConfig { steps: 4, ..Default::default() }
+}
+
+#[test]
+fn mosquito_stroke_teeth_pass_without_collision() {
+    let report = Mosquito::new(Settings::default()).stroke(&cfg());
+    assert_eq!(report.steps.len(), 5, "{}", report);
+    assert_eq!(report.steps[4].angle, 0.35);
+    // The inner teeth sit between the outer ones and slide past them
+    assert!(report.first_overlap().is_none(), "{}", report);
+    assert_eq!(report.penetration(), 0.0, "{}", report);
+    assert!(report.first_collision().is_none(), "{}", report);
+}
+
+#[test]
+fn mosquito_stroke_finds_overlapping_teeth() {
+    // Inner needles on top of the outer ones
+    let mut given = Settings::default().given;
+    let d = given.needle_distance;
+    given.inner_needles = Some(NeedlePlacement::grid(-d / 2.0, d, 3, 0.0, 0.0, 1));
+    let report = Mosquito::new(Settings::new(given)).stroke(&cfg());
+    let first = report.first_overlap().expect("teeth overlap");
+    assert_eq!(first.angle, 0.0, "{}", report);
+    assert!(report.penetration() >= -first.teeth.as_ref().unwrap().separation, "{}", report);
+    assert!(report.penetration() > 1.0, "{}", report);
+}
+
+#[test]
+fn mosquito_stroke_without_inner_needles() {
+    let mut given = Settings::default().given;
+    given.inner_needles = Some(vec![]);
+    let report = Mosquito::new(Settings::new(given)).stroke(&cfg());
+    assert!(report.steps.iter().all(|step| step.teeth.is_none()), "{}", report);
+    assert!(report.first_overlap().is_none(), "{}", report);
+    assert_eq!(report.penetration(), 0.0, "{}", report);
+    assert!(report.to_string().contains("no teeth"), "{}", report);
+}
+
+#[test]
+fn mosquito_stroke_pose_moves_the_inner_assembly() {
+    let cfg = Settings::default();
+    let pivot = Vector3::new(-cfg.given.first_needle_x as f32, 0.0, 5.0);
+    let mosquito = Mosquito::new(cfg);
+    let pose = mosquito.stroke_pose(0.2);
+    assert!((pose.to_inner(pivot) - pivot).x.abs() < 1e-4);
+    assert!((pose.to_inner(pivot) - pivot).y.abs() < 1e-4);
+
+    let (p, assembly) = (Vector3::new(20.0, -30.0, 0.0), mosquito.assembly());
+    for part in MosquitoPart::ALL {
+        let moved = if part.moves_with_stroke() { pose.to_inner(p) } else { p };
+        assert_eq!(pose.part_distance(part.id(), p), assembly.part_distance(part.id(), moved), "{:?}", part);
+    }
+}
+
+#[test]
+fn mosquito_stroke_png_without_steps() {
+    let cfg = StrokeConfig { steps: 0, ..Default::default() };
+    assert_eq!(cfg.angles(), vec![cfg.angle]);
+    let path = std::env::temp_dir().join(format!("mosquito_stroke_{}.png", std::process::id()));
+    Mosquito::new(Settings::default()).write_stroke_png(&cfg, 4.0, path.to_str().unwrap()).unwrap();
+    assert!(std::fs::metadata(&path).unwrap().len() > 0);
+    std::fs::remove_file(path).unwrap();
+}
@@ -0,0 +1,35 @@
+// Wall thickness of the printed holders and the steel needle plates, see press_common::thickness
+
+use press_common::bounds::{AutoBounds, BoundsConfig};
+use press_common::mesh::MeshConfig;
+use press_common::part::PartSurface;
+use press_common::thickness::{thickness, ThicknessConfig, ThicknessReport};
+use press_mosquito::{Mosquito, MosquitoPart, Settings};
+
+fn report(part: MosquitoPart, min_thickness: f32) -> ThicknessReport {
+    let assembly = Mosquito::new(Settings::default()).assembly();
+    let surface = AutoBounds::new(PartSurface::new(&assembly, part.id()), &BoundsConfig::default(), 1.0);
+    let cfg = ThicknessConfig { min_thickness, mesh: MeshConfig { resolution: 64 }, ..Default::default() };
+    thickness(&surface, &cfg)
+}
+
+#[test]
+fn holder_walls_are_printable() {
+    let report = report(MosquitoPart::InnerHolder, 1.0);
+    assert!(report.regions.is_empty(), "{}", report);
+    // Gaps narrower than the mesh cells stop a few vertices from reaching the surface, and
+    // unmeasured vertices fail the check. Keep them from spreading.
+    assert!(!report.is_ok());
+    assert!(report.unmeasured() < 30, "{}", report);
+}
+
+#[test]
+fn needle_plates_are_steel_thick() {
+    // `steel_thickness` is on both sides of the mid-plane
+    let steel = 2.0 * Settings::default().given.steel_thickness as f32;
+    let report = report(MosquitoPart::InnerNeedle, steel + 0.5);
+    assert!(!report.is_ok());
+    // Most of the surface are the two faces of the plates
+    let faces = report.thickness.iter().filter(|&&t| (t - steel).abs() < 0.2).count();
+    assert!(faces * 2 > report.thickness.len(), "{} of {}\n{}", faces, report.thickness.len(), report);
+}
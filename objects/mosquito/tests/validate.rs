@@ -0,0 +1,14 @@
+// Distance-property checks, see press_common::validate
+
+use press_common::validate::{validate, ValidationConfig};
+use press_mosquito::{Mosquito, Settings};
+
+#[test]
+fn mosquito_distance_property() {
+    let cfg = ValidationConfig { samples: 5000, ..Default::default() };
+    let report = validate(&Mosquito::new(Settings::default()), &cfg);
+    assert!(report.bounding_box_violations.is_empty(), "{}", report);
+    assert!(report.sign_violations.is_empty(), "{}", report);
+    // The cosine hill profiles are not exact distances, keep them from getting worse
+    assert!(report.lipschitz < 1.3, "{}", report);
+}
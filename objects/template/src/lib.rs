@@ -2,6 +2,8 @@
 // This is a simple sphere example - modify as needed
 
 pub use cgmath::Vector3;
+pub use press_common::dual::{distance_gradient, Dual};
+pub use press_common::{create_computation, Scalar};
 pub use sdf_viewer::sdf::{ffi::set_root_sdf, SDFSample, SDFSurface};
 
 /// Entry point called when the WASM module is loaded
@@ -39,6 +41,28 @@ impl Default for MyObject {
     }
 }
 
+// Written against `Scalar`, the same fields sample on f64 and give exact gradients on `Dual`
+create_computation! {
+    Config, S: Scalar;
+
+    center_distance: S => |slf: &Computation<S>| (slf.x * slf.x + slf.y * slf.y + slf.z * slf.z).sqrt(),
+}
+
+impl MyObject {
+    /// Signed distance at p, on any `Scalar`
+    pub fn distance<S: Scalar>(&self, p: Vector3<S>) -> S {
+        let comp = Computation::new(&self.cfg, p.x, p.y, p.z);
+
+        // Simple sphere SDF: distance = sqrt(x^2 + y^2 + z^2) - radius
+        comp.center_distance() - S::from_f64(comp.cfg.radius)
+    }
+
+    /// Signed distance and its exact gradient at p
+    pub fn distance_gradient(&self, p: Vector3<f32>) -> (f32, Vector3<f32>) {
+        distance_gradient(|p| self.distance(p), p)
+    }
+}
+
 impl SDFSurface for MyObject {
     /// Define the bounding box for your object
     fn bounding_box(&self) -> [Vector3<f32>; 2] {
@@ -51,12 +75,7 @@ impl SDFSurface for MyObject {
 
     /// Sample the SDF at point p
     fn sample(&self, p: Vector3<f32>, _distance_only: bool) -> SDFSample {
-        let x = p.x as f64;
-        let y = p.y as f64;
-        let z = p.z as f64;
-
-        // Simple sphere SDF: distance = sqrt(x^2 + y^2 + z^2) - radius
-        let distance = (x * x + y * y + z * z).sqrt() - self.cfg.radius;
+        let distance = self.distance(press_common::scalar::convert::<f64>(p));
 
         SDFSample::new(
             distance as f32,
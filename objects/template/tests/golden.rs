@@ -0,0 +1,13 @@
+// Golden-field regression tests, update with PRESS_UPDATE_GOLDEN=1 cargo test -p press-template
+
+use press_common::golden::assert_golden_field;
+use press_template::MyObject;
+
+fn snapshot(name: &str) -> String {
+    format!("{}/tests/golden/{}.txt", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+#[test]
+fn object_field() {
+    assert_golden_field(&MyObject::default(), snapshot("object"), 16, 1e-4);
+}
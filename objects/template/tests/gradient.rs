@@ -0,0 +1,33 @@
+// Exact gradients on dual numbers, see press_common::dual
+
+use press_common::{cylinder_between, rotate_z, translate, InnerSpace};
+use press_template::{Dual, MyObject, Scalar, Vector3};
+
+#[test]
+fn sphere_gradient_is_normal() {
+    let obj = MyObject::default();
+    let p = Vector3::new(3.0, -4.0, 12.0);
+    let (distance, gradient) = obj.distance_gradient(p);
+    assert!((distance - 3.0).abs() < 1e-6);
+    assert!((gradient - p / 13.0).magnitude() < 1e-6, "{:?}", gradient);
+}
+
+/// A rotated, shifted rod through the common helpers
+fn rod<S: Scalar>(p: Vector3<S>) -> S {
+    let c = |v: f64| S::from_f64(v);
+    let p = rotate_z(translate(p, Vector3::new(c(1.0), c(2.0), c(0.0))), c(0.3));
+    cylinder_between(p, Vector3::new(c(0.0), c(0.0), c(-5.0)), Vector3::new(c(0.0), c(0.0), c(5.0)), c(0.5))
+}
+
+#[test]
+fn helpers_match_finite_differences() {
+    let h = 1e-6;
+    for p in [Vector3::new(3.0, 1.0, 2.0), Vector3::new(-1.0, 4.0, 7.0), Vector3::new(1.2, 2.1, -0.5)] {
+        let d = rod(Dual::variables(p));
+        assert_eq!(d.value, rod(p));
+        for (axis, e) in [(0, Vector3::unit_x()), (1, Vector3::unit_y()), (2, Vector3::unit_z())] {
+            let fd = (rod(p + e * h) - rod(p - e * h)) / (2.0 * h);
+            assert!((d.gradient[axis] - fd).abs() < 1e-6, "{:?} axis {}: {} vs {}", p, axis, d.gradient[axis], fd);
+        }
+    }
+}
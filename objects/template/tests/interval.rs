@@ -0,0 +1,82 @@
+// Distance bounds over boxes, see press_common::interval
+
+use press_common::interval::{partition, BoxClass, Interval};
+use press_common::{cylinder_between, rotate_axis};
+use press_template::{MyObject, SDFSurface, Scalar, Vector3};
+
+/// A rod rotated by the point dependent angle `x`, through the common helpers
+fn twisted_rod<S: Scalar>(p: Vector3<S>) -> S {
+    let c = |v: f64| S::from_f64(v);
+    let p = rotate_axis(p, Vector3::new(c(1.0), c(1.0), c(0.0)), p.x.atan2(p.y + c(20.0)));
+    cylinder_between(p, Vector3::new(c(0.0), c(0.0), c(-5.0)), Vector3::new(c(0.0), c(0.0), c(5.0)), c(0.5))
+}
+
+#[test]
+fn interval_bounds_samples() {
+    let obj = MyObject::default();
+    let boxes = [
+        (Vector3::new(-3.0, -2.0, -1.0), Vector3::new(1.0, 4.0, 2.0)),
+        (Vector3::new(8.0, -1.0, 0.5), Vector3::new(11.0, 0.5, 1.5)),
+        (Vector3::new(-14.0, -14.0, -14.0), Vector3::new(-9.0, -9.0, -9.0)),
+    ];
+    for (lo, hi) in boxes {
+        let bound = obj.distance(Interval::from_box(lo, hi));
+        for i in 0..=4 {
+            for j in 0..=4 {
+                for k in 0..=4 {
+                    let t = Vector3::new(i as f32, j as f32, k as f32) / 4.0;
+                    let p = lo + Vector3::new((hi - lo).x * t.x, (hi - lo).y * t.y, (hi - lo).z * t.z);
+                    let d = obj.sample(p, true).distance as f64;
+                    assert!(bound.lo - 1e-5 <= d && d <= bound.hi + 1e-5, "{} outside {:?}", d, bound);
+                }
+            }
+        }
+    }
+    let class = |lo, hi| obj.distance(Interval::from_box(lo, hi)).classify();
+    assert_eq!(class(Vector3::new(-1.0, -1.0, -1.0), Vector3::new(1.0, 1.0, 1.0)), BoxClass::Inside);
+    assert_eq!(class(Vector3::new(11.0, 0.0, 0.0), Vector3::new(12.0, 1.0, 1.0)), BoxClass::Outside);
+    assert_eq!(class(Vector3::new(9.0, -1.0, -1.0), Vector3::new(11.0, 1.0, 1.0)), BoxClass::Boundary);
+}
+
+#[test]
+fn partition_encloses_sphere() {
+    let obj = MyObject::default();
+    let partition = partition(|p| obj.distance(p), obj.bounding_box(), 5);
+    let [lo, hi] = partition.enclosure().unwrap();
+    let cell = (obj.bounding_box()[1].x - obj.bounding_box()[0].x) / 32.0;
+    for axis in 0..3 {
+        assert!(lo[axis] <= -10.0 && lo[axis] > -10.0 - cell, "{:?}", lo);
+        assert!(hi[axis] >= 10.0 && hi[axis] < 10.0 + cell, "{:?}", hi);
+    }
+    let (min, max) = partition.volume_bounds();
+    let volume = 4.0 / 3.0 * std::f64::consts::PI * 1000.0;
+    assert!(min < volume && volume < max, "{} {} {}", min, volume, max);
+    // Far fewer evaluations than the 32768 cells of the finest level
+    assert!(partition.evaluations < 8000, "{}", partition.evaluations);
+}
+
+#[test]
+fn helpers_bound_samples() {
+    let (lo, hi) = (Vector3::new(-2.0, 1.0, -3.0), Vector3::new(3.0, 2.5, 4.0));
+    let bound = twisted_rod(Interval::from_box(lo, hi));
+    for i in 0..=10 {
+        for j in 0..=10 {
+            for k in 0..=10 {
+                let t = Vector3::new(i as f64, j as f64, k as f64) / 10.0;
+                let (lo, hi) = (lo.cast::<f64>().unwrap(), hi.cast::<f64>().unwrap());
+                let p = lo + Vector3::new((hi - lo).x * t.x, (hi - lo).y * t.y, (hi - lo).z * t.z);
+                let d = twisted_rod(p);
+                assert!(bound.lo <= d && d <= bound.hi, "{} outside {:?}", d, bound);
+            }
+        }
+    }
+}
+
+#[test]
+fn arithmetic_rounds_outward() {
+    // Exactly 2^-55 on these doubles, rounding each step to nearest gives 2^-54
+    let d = Interval::point(0.1) + Interval::point(0.2) - Interval::point(0.3);
+    assert!(d.contains(2f64.powi(-55)), "{:?}", d);
+    let third = Interval::point(1.0) / Interval::point(3.0);
+    assert!(third.lo < 1.0 / 3.0 && 1.0 / 3.0 < third.hi, "{:?}", third);
+}
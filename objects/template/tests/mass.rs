@@ -0,0 +1,68 @@
+// Adaptive mass integration, see press_common::mass
+
+use std::f64::consts::PI;
+
+use press_common::mass::{combine, mass_properties, MassConfig, MassProperties};
+use press_template::{MyObject, SDFSample, SDFSurface, Vector3};
+
+/// The template sphere moved by `offset`.
+struct Moved(MyObject, Vector3<f32>);
+
+impl SDFSurface for Moved {
+    fn bounding_box(&self) -> [Vector3<f32>; 2] {
+        let [lo, hi] = self.0.bounding_box();
+        [lo + self.1, hi + self.1]
+    }
+
+    fn sample(&self, p: Vector3<f32>, distance_only: bool) -> SDFSample {
+        self.0.sample(p - self.1, distance_only)
+    }
+}
+
+const DENSITY: f64 = 2.0;
+
+fn sphere(offset: Vector3<f32>) -> MassProperties {
+    mass_properties(&Moved(MyObject::default(), offset), DENSITY, &MassConfig::default())
+}
+
+fn assert_close(value: f64, expected: f64, tolerance: f64, what: &str) {
+    assert!((value - expected).abs() <= tolerance, "{}: {} != {} +- {}", what, value, expected, tolerance);
+}
+
+#[test]
+fn sphere_matches_closed_form() {
+    let r = MyObject::default().cfg.radius;
+    let offset = Vector3::new(3.0, -2.0, 5.0);
+    let props = sphere(offset);
+    let volume = 4.0 / 3.0 * PI * r.powi(3);
+    assert_close(props.volume, volume, props.volume_error, "volume");
+    assert_close(props.mass, props.volume * DENSITY, 1e-9, "mass");
+    assert_close(props.area, 4.0 * PI * r * r, 0.02 * 4.0 * PI * r * r, "area");
+    for axis in 0..3 {
+        assert_close(props.center_of_mass[axis], offset[axis] as f64, 1e-3 * r, "center of mass");
+    }
+    // A solid sphere has 2/5 m r² about every axis through its center
+    let moment = 0.4 * props.mass * r * r;
+    for i in 0..3 {
+        for j in 0..3 {
+            let expected = if i == j { moment } else { 0.0 };
+            assert_close(props.inertia[i][j], expected, 1e-3 * moment, "inertia");
+        }
+    }
+}
+
+#[test]
+fn combine_applies_parallel_axes() {
+    let d = 15.0;
+    let (a, b) = (sphere(Vector3::new(-d, 0.0, 0.0)), sphere(Vector3::new(d, 0.0, 0.0)));
+    let both = combine(&[a, b]);
+    assert_close(both.mass, a.mass + b.mass, 1e-9, "mass");
+    assert_close(both.volume, a.volume + b.volume, 1e-9, "volume");
+    assert_close(both.center_of_mass.x, 0.0, 1e-3, "center of mass");
+    // Each sphere's own inertia plus m d² about the axes across the line between them
+    let own = a.inertia.x.x;
+    let shift = a.mass * (d as f64).powi(2);
+    assert_close(both.inertia.x.x, 2.0 * own, 1e-6 * own, "inertia along the line");
+    assert_close(both.inertia.y.y, 2.0 * own + 2.0 * shift, 1e-6 * shift, "inertia across the line");
+    assert_close(both.inertia.z.z, 2.0 * own + 2.0 * shift, 1e-6 * shift, "inertia across the line");
+}